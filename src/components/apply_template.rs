@@ -0,0 +1,66 @@
+use dioxus::prelude::*;
+use crate::components::edit_items;
+use crate::types::{replace_with_template, AppSettings, EditHistory, FormatterItem, PlaylistItem, ServiceTemplate};
+
+/// Template picker for the playlist panel - fills a saved template from the loaded playlist
+#[component]
+pub fn ApplyTemplate(
+    formatter_items: Signal<Vec<FormatterItem>>,
+    history: Signal<EditHistory>,
+    selected_group_idx: Signal<Option<usize>>,
+    playlist_items: Vec<PlaylistItem>,
+    logs: Signal<Vec<String>>,
+) -> Element {
+    // Reloaded on mount so templates edited on the Templates page show up
+    let templates = use_signal(ServiceTemplate::load_all);
+
+    let mut add_log = move |msg: String| {
+        let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
+        logs.write().push(format!("[{}] {}", timestamp, msg));
+    };
+
+    if templates.read().is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "mt-3",
+            label { class: "text-[0.7rem] font-bold text-zinc-500 tracking-wider mb-2 block uppercase", "APPLY TEMPLATE" }
+            select {
+                class: "w-full bg-zinc-950 border border-zinc-700/50 text-zinc-200 p-2.5 rounded font-mono text-sm focus:outline-none focus:border-emerald-500/50 focus:bg-emerald-500/5 transition-all appearance-none cursor-pointer",
+                disabled: playlist_items.is_empty(),
+                value: "",
+                onchange: move |e| {
+                    let Ok(idx) = e.value().parse::<usize>() else { return };
+                    let Some(template) = templates.read().get(idx).cloned() else { return };
+                    let fill = template.apply(&playlist_items, &AppSettings::load().rules);
+                    // The template only replaces playlist entries; references, delays,
+                    // milestones and manual entries stay in the plan
+                    let mut replaced = 0;
+                    edit_items(formatter_items, history, format!("Apply template {}", template.name), |items| {
+                        replaced = replace_with_template(items, fill.items);
+                    });
+                    selected_group_idx.set(None);
+                    add_log(format!("Applied template: {}", template.name));
+                    if replaced > 0 {
+                        add_log(format!("Replaced {} playlist entries already in the plan", replaced));
+                    }
+                    if !fill.unfilled.is_empty() {
+                        add_log(format!(
+                            "⚠ {} template slots left empty: {}",
+                            fill.unfilled.len(),
+                            fill.unfilled.join(", ")
+                        ));
+                    }
+                    if !fill.unused.is_empty() {
+                        add_log(format!("{} playlist items are not in the template", fill.unused.len()));
+                    }
+                },
+                option { value: "", "-- Select Template --" }
+                for (idx, template) in templates.read().iter().enumerate() {
+                    option { value: "{idx}", "{template.name}" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use dioxus::prelude::*;
+use crate::components::{edit_items, keep_text_undo, DurationInput};
+use crate::types::{
+    move_entries_to_group, ripple_edit, take_entries, wrap_in_group, Duration, EditHistory, EntryPath, FormatterItem,
+    TimeField, TimedEntry,
+};
+use crate::utils::parse_end_time;
+
+const BUTTON_CLASS: &str = "px-2 py-1 rounded border border-zinc-700 text-zinc-400 hover:text-cyan-500 hover:border-cyan-500/50 transition-all";
+
+/// Click on an entry: Ctrl/Cmd toggles it, Shift selects the range from the
+/// last clicked entry, a plain click selects just that entry
+pub fn select_entry(
+    mut selection: Signal<Vec<EntryPath>>,
+    mut anchor: Signal<Option<EntryPath>>,
+    items: &[FormatterItem],
+    path: EntryPath,
+    modifiers: Modifiers,
+) {
+    if modifiers.shift() {
+        if let Some(from) = anchor() {
+            let order = EntryPath::all(items);
+            if let (Some(a), Some(b)) = (
+                order.iter().position(|p| *p == from),
+                order.iter().position(|p| *p == path),
+            ) {
+                selection.set(order[a.min(b)..=a.max(b)].to_vec());
+                return;
+            }
+        }
+    }
+    if modifiers.ctrl() || modifiers.meta() {
+        let mut current = selection.write();
+        match current.iter().position(|p| *p == path) {
+            Some(pos) => {
+                current.remove(pos);
+            }
+            None => current.push(path),
+        }
+    } else if selection() == [path] {
+        selection.set(vec![]);
+    } else {
+        selection.set(vec![path]);
+    }
+    anchor.set(Some(path));
+}
+
+/// Actions applied to every selected entry at once
+#[component]
+pub fn BulkEditBar(
+    formatter_items: Signal<Vec<FormatterItem>>,
+    history: Signal<EditHistory>,
+    selection: Signal<Vec<EntryPath>>,
+    /// Entries the fit-to-time dialog is open for
+    fit_paths: Signal<Option<Vec<EntryPath>>>,
+    /// Whether timing edits ripple to the entries after them
+    ripple: Signal<bool>,
+) -> Element {
+    let mut duration = use_signal(|| Option::<Duration>::None);
+    let mut end_text = use_signal(String::new);
+    let mut color = use_signal(|| "#779BE7".to_string());
+
+    let paths = selection();
+    if paths.is_empty() {
+        return rsx! {};
+    }
+    let end_time = parse_end_time(&end_text());
+    let groups: Vec<(usize, String)> = formatter_items
+        .read()
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| match item {
+            FormatterItem::Group { name, .. } => Some((idx, name.clone())),
+            _ => None,
+        })
+        .collect();
+
+    // Apply `edit` to each selected entry as one undo step
+    let edit_selected = move |label: String, edit: &dyn Fn(&mut TimedEntry)| {
+        let paths = selection();
+        edit_items(formatter_items, history, label, |items| {
+            for path in paths {
+                if let Some(entry) = path.entry_mut(items) {
+                    edit(entry);
+                }
+            }
+        });
+    };
+    // Like `edit_selected` for durations and end times, rippling each change when ripple is on
+    let edit_timing = move |label: String, field: TimeField, edit: &dyn Fn(&mut TimedEntry)| {
+        let mut paths = selection();
+        // Plan order, so each ripple starts from times the earlier ones already moved
+        paths.sort();
+        let ripple_on = ripple();
+        edit_items(formatter_items, history, label, |items| {
+            for path in paths {
+                let Some(entry) = path.entry_mut(items) else { continue };
+                let (old_end, old_duration) = (entry.end_time, entry.duration);
+                edit(entry);
+                if ripple_on {
+                    ripple_edit(items, path, old_end, old_duration, field);
+                }
+            }
+        });
+    };
+    let all_selected = move |flag: fn(&TimedEntry) -> bool| {
+        let items = formatter_items.read();
+        selection().iter().filter_map(|p| p.entry(&items)).all(flag)
+    };
+
+    rsx! {
+        div { class: "px-4 py-2 border-b border-cyan-500/30 bg-cyan-500/5 flex flex-wrap items-center gap-2 text-[0.65rem] font-bold uppercase tracking-wider",
+            span { class: "text-cyan-500", "{paths.len()} SELECTED" }
+            div { class: "flex items-center gap-1 w-32",
+                DurationInput {
+                    value: duration(),
+                    placeholder: "duration",
+                    on_change: move |d| duration.set(d),
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    disabled: duration().is_none(),
+                    onclick: move |_| {
+                        if let Some(d) = duration() {
+                            edit_timing(format!("Set duration on {} entries", selection.read().len()), TimeField::Duration, &|entry| entry.duration = d);
+                        }
+                    },
+                    "SET"
+                }
+            }
+            div { class: "flex items-center gap-1 w-32",
+                input {
+                    class: if end_time.is_err() && !end_text().is_empty() { "w-full bg-zinc-950 border border-red-500/50 rounded px-2 py-1.5 text-xs font-mono text-zinc-200 focus:outline-none" } else { "w-full bg-zinc-950 border border-zinc-800 rounded px-2 py-1.5 text-xs font-mono text-zinc-200 focus:outline-none focus:border-emerald-500/50 placeholder-zinc-700" },
+                    placeholder: "end time",
+                    title: end_time.as_ref().err().map(|e| e.to_string()).unwrap_or_default(),
+                    value: "{end_text}",
+                    onkeydown: keep_text_undo,
+                    oninput: move |e| end_text.set(e.value()),
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    disabled: end_time.is_err(),
+                    onclick: move |_| {
+                        if let Ok(end) = parse_end_time(&end_text()) {
+                            edit_timing(format!("Set end time on {} entries", selection.read().len()), TimeField::EndTime, &|entry| entry.end_time = end);
+                        }
+                    },
+                    "SET"
+                }
+            }
+            button {
+                class: BUTTON_CLASS,
+                title: "Toggle count to end",
+                onclick: move |_| {
+                    let on = !all_selected(|e| e.count_to_end);
+                    edit_selected("Toggle count to end".to_string(), &|entry| entry.count_to_end = on);
+                },
+                "CTE"
+            }
+            button {
+                class: BUTTON_CLASS,
+                title: "Toggle link start",
+                onclick: move |_| {
+                    let on = !all_selected(|e| e.link_start);
+                    edit_selected("Toggle link start".to_string(), &|entry| entry.link_start = on);
+                },
+                "LINK"
+            }
+            div { class: "flex items-center gap-1",
+                input {
+                    r#type: "color",
+                    class: "w-7 h-6 bg-transparent cursor-pointer",
+                    value: "{color}",
+                    oninput: move |e| color.set(e.value()),
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    onclick: move |_| {
+                        let c = color();
+                        edit_selected("Change entry colour".to_string(), &|entry| entry.color = c.clone());
+                    },
+                    "COLOUR"
+                }
+                button {
+                    class: BUTTON_CLASS,
+                    title: "Remove colour",
+                    onclick: move |_| edit_selected("Clear entry colour".to_string(), &|entry| entry.color.clear()),
+                    "×"
+                }
+            }
+            if !groups.is_empty() {
+                select {
+                    class: "bg-zinc-950 border border-zinc-700 text-zinc-400 px-1 py-1 rounded font-mono text-[0.65rem] focus:outline-none cursor-pointer",
+                    value: "",
+                    onchange: move |e| {
+                        let Ok(target) = e.value().parse::<usize>() else { return };
+                        let paths = selection();
+                        edit_items(formatter_items, history, format!("Move {} entries to group", paths.len()), |items| {
+                            move_entries_to_group(items, &paths, target);
+                        });
+                        selection.set(vec![]);
+                    },
+                    option { value: "", "MOVE TO…" }
+                    for (idx, name) in groups {
+                        option { value: "{idx}", "{name}" }
+                    }
+                }
+            }
+            button {
+                class: BUTTON_CLASS,
+                title: "Scale the selected entries to a target end time or length",
+                onclick: move |_| {
+                    let mut paths = selection();
+                    paths.sort();
+                    fit_paths.set(Some(paths));
+                },
+                "FIT"
+            }
+            button {
+                class: BUTTON_CLASS,
+                title: "Wrap the selected entries in a new group",
+                onclick: move |_| {
+                    let paths = selection();
+                    let group_count = formatter_items
+                        .read()
+                        .iter()
+                        .filter(|i| matches!(i, FormatterItem::Group { .. }))
+                        .count();
+                    edit_items(formatter_items, history, format!("Group {} entries", paths.len()), |items| {
+                        wrap_in_group(items, &paths, format!("GROUP {}", group_count + 1), "#779BE7".to_string());
+                    });
+                    selection.set(vec![]);
+                },
+                "GROUP"
+            }
+            button {
+                class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 hover:text-red-500 hover:border-red-500/50 transition-all",
+                onclick: move |_| {
+                    let paths = selection();
+                    edit_items(formatter_items, history, format!("Delete {} entries", paths.len()), |items| {
+                        take_entries(items, &paths);
+                    });
+                    selection.set(vec![]);
+                },
+                "DELETE"
+            }
+            button {
+                class: "ml-auto px-2 py-1 rounded text-zinc-500 hover:text-zinc-200 transition-all",
+                title: "Clear selection",
+                onclick: move |_| selection.set(vec![]),
+                "✕"
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use dioxus::prelude::*;
+use crate::components::keep_text_undo;
+use crate::types::Duration;
+use crate::utils::parse_duration;
+
+const INPUT_CLASS: &str = "w-full bg-zinc-950 border border-zinc-800 rounded px-2 py-1.5 text-xs font-mono text-zinc-200 focus:outline-none focus:border-emerald-500/50 transition-all placeholder-zinc-700";
+const INVALID_INPUT_CLASS: &str = "w-full bg-zinc-950 border border-red-500/50 rounded px-2 py-1.5 text-xs font-mono text-zinc-200 focus:outline-none focus:border-red-500";
+
+/// Compact duration text field that only commits values the time parser accepts.
+/// Clearing the field reports `None`.
+#[component]
+pub fn DurationInput(
+    value: Option<Duration>,
+    on_change: EventHandler<Option<Duration>>,
+    #[props(default)] placeholder: String,
+) -> Element {
+    let mut draft = use_signal(|| value.map(|d| d.to_string()).unwrap_or_default());
+    let mut error = use_signal(|| Option::<String>::None);
+
+    // Rows are usually keyed by position, so pick up the new value when a row above is removed
+    use_effect(use_reactive!(|value| {
+        let current = match draft.peek().trim() {
+            "" => None,
+            text => parse_duration(text).ok(),
+        };
+        if current != value {
+            draft.set(value.map(|d| d.to_string()).unwrap_or_default());
+            error.set(None);
+        }
+    }));
+
+    rsx! {
+        input {
+            class: if error.read().is_some() { INVALID_INPUT_CLASS } else { INPUT_CLASS },
+            title: error.read().clone().unwrap_or_default(),
+            placeholder: "{placeholder}",
+            value: "{draft}",
+            onkeydown: keep_text_undo,
+            oninput: move |e| {
+                draft.set(e.value());
+                if e.value().trim().is_empty() {
+                    error.set(None);
+                    on_change.call(None);
+                    return;
+                }
+                match parse_duration(&e.value()) {
+                    Ok(d) => {
+                        error.set(None);
+                        on_change.call(Some(d));
+                    }
+                    Err(err) => error.set(Some(err.to_string())),
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,13 @@
+/// Ask where to save an export, offering `extension` as the only file type.
+/// Returns the path written to, or None if the dialog was cancelled.
+pub async fn save_export(contents: impl AsRef<[u8]>, name: &str, extension: &str) -> Result<Option<String>, String> {
+    let handle = rfd::AsyncFileDialog::new()
+        .add_filter(extension.to_uppercase(), &[extension])
+        .set_file_name(format!("{}.{}", name, extension))
+        .save_file()
+        .await;
+    let Some(handle) = handle else { return Ok(None) };
+    let path = handle.path().to_path_buf();
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(Some(path.display().to_string()))
+}
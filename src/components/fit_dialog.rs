@@ -0,0 +1,186 @@
+use dioxus::prelude::*;
+use crate::components::{edit_items, keep_text_undo};
+use crate::types::{fit_durations, Duration, EditHistory, EntryPath, FormatterItem, TimeOfDay};
+use crate::utils::{parse_duration, parse_end_time};
+
+const INPUT_CLASS: &str = "w-full bg-zinc-950 border border-zinc-800 rounded px-2 py-1.5 text-xs font-mono text-zinc-200 focus:outline-none focus:border-emerald-500/50 transition-all placeholder-zinc-700";
+
+/// Scale the flexible entries at `paths` to finish by a target end time or run
+/// for a target length. Shows the proposed durations before anything changes.
+#[component]
+pub fn FitDialog(
+    formatter_items: Signal<Vec<FormatterItem>>,
+    history: Signal<EditHistory>,
+    paths: Vec<EntryPath>,
+    on_close: EventHandler<()>,
+) -> Element {
+    // Plan order, whatever order the entries were selected in
+    let paths: Vec<EntryPath> = {
+        let items = formatter_items.read();
+        EntryPath::all(&items).into_iter().filter(|p| paths.contains(p) && p.entry(&items).is_some()).collect()
+    };
+    let entries: Vec<_> = {
+        let items = formatter_items.read();
+        paths.iter().filter_map(|p| p.entry(&items).cloned()).collect()
+    };
+    let initial_fixed: Vec<bool> = entries.iter().map(|e| e.fixed).collect();
+    let mut fixed = use_signal(|| initial_fixed);
+    let mut by_end = use_signal(|| true);
+    let mut target_text = use_signal(String::new);
+
+    // Start of the first entry, when its end time is set
+    let start = entries
+        .first()
+        .filter(|e| !e.end_time.is_midnight())
+        .map(|e| TimeOfDay::from_schedule_ms(e.end_time.schedule_ms().saturating_sub(e.duration.as_ms())));
+    let current_total = entries.iter().fold(Duration::default(), |total, e| total + e.duration);
+    let has_locked = entries.iter().any(|e| e.locked);
+
+    let target: Result<Duration, String> = match target_text().trim() {
+        "" => Err(String::new()),
+        text if by_end() => match (parse_end_time(text), start) {
+            (Err(e), _) => Err(e.to_string()),
+            (Ok(_), None) => Err("The first entry has no end time - fit to a length instead".to_string()),
+            (Ok(end), Some(start)) => {
+                let end = end.roll_forward_from(start);
+                Ok(Duration::from_ms(end.schedule_ms() - start.schedule_ms()))
+            }
+        },
+        text => parse_duration(text).map_err(|e| e.to_string()),
+    };
+    let fixed_now = fixed();
+    let proposal = target.clone().and_then(|target| {
+        let inputs: Vec<(Duration, bool)> = entries
+            .iter()
+            .zip(fixed_now.iter())
+            .map(|(e, fixed)| (e.duration, *fixed))
+            .collect();
+        fit_durations(&inputs, target)
+    });
+
+    rsx! {
+        div { class: "time-picker-overlay", onclick: move |_| on_close.call(()),
+            div {
+                class: "time-picker-modal",
+                style: "width: 560px;",
+                onclick: move |e| e.stop_propagation(),
+                div { class: "tp-header", "Fit {entries.len()} entries to time" }
+                div { class: "flex items-center gap-2",
+                    button {
+                        class: if by_end() { "px-2 py-1 rounded border border-cyan-500/50 text-cyan-500 text-[0.65rem] font-bold" } else { "px-2 py-1 rounded border border-zinc-700 text-zinc-500 text-[0.65rem] font-bold" },
+                        onclick: move |_| by_end.set(true),
+                        "END BY"
+                    }
+                    button {
+                        class: if !by_end() { "px-2 py-1 rounded border border-cyan-500/50 text-cyan-500 text-[0.65rem] font-bold" } else { "px-2 py-1 rounded border border-zinc-700 text-zinc-500 text-[0.65rem] font-bold" },
+                        onclick: move |_| by_end.set(false),
+                        "TOTAL LENGTH"
+                    }
+                    input {
+                        class: INPUT_CLASS,
+                        placeholder: if by_end() { "10:25" } else { "00:20:00" },
+                        value: "{target_text}",
+                        onkeydown: keep_text_undo,
+                        oninput: move |e| target_text.set(e.value()),
+                    }
+                }
+                div { class: "flex flex-col gap-1 max-h-[40vh] overflow-y-auto",
+                    div { class: "grid grid-cols-[1fr_80px_80px_50px] gap-2 px-1 text-[0.6rem] font-bold text-zinc-600 uppercase tracking-wider",
+                        span { "ENTRY" }
+                        span { "NOW" }
+                        span { "PROPOSED" }
+                        span { title: "Fixed entries keep their duration", "FIXED" }
+                    }
+                    for (idx, entry) in entries.iter().enumerate() {
+                        div { key: "{idx}", class: "grid grid-cols-[1fr_80px_80px_50px] gap-2 items-center text-xs font-mono",
+                            span { class: "truncate text-zinc-300", "{entry.name}" }
+                            span { class: "text-zinc-500", "{entry.duration}" }
+                            match proposal.as_ref().ok().and_then(|p| p.get(idx)) {
+                                Some(proposed) if *proposed != entry.duration => rsx! {
+                                    span { class: if *proposed > entry.duration { "text-emerald-500" } else { "text-amber-500" }, "{proposed}" }
+                                },
+                                Some(proposed) => rsx! {
+                                    span { class: "text-zinc-500", "{proposed}" }
+                                },
+                                None => rsx! {
+                                    span { class: "text-zinc-700", "—" }
+                                },
+                            }
+                            input {
+                                r#type: "checkbox",
+                                class: "w-4 h-4 accent-emerald-500 cursor-pointer",
+                                checked: fixed_now.get(idx).copied().unwrap_or(false),
+                                onchange: move |e| {
+                                    if let Some(flag) = fixed.write().get_mut(idx) {
+                                        *flag = e.checked();
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+                if has_locked {
+                    div { class: "text-xs text-amber-500",
+                        "⚠ Locked entries keep their end times; only their durations change"
+                    }
+                }
+                div { class: "flex justify-between text-xs font-mono text-zinc-500",
+                    span { "Now: {current_total}" }
+                    match (&target, &proposal) {
+                        (Ok(target), Ok(_)) => rsx! {
+                            span { class: "text-cyan-500", "Target: {target}" }
+                        },
+                        (_, Err(e)) if !e.is_empty() => rsx! {
+                            span { class: "text-red-500", "{e}" }
+                        },
+                        _ => rsx! {},
+                    }
+                }
+                div { class: "flex gap-2",
+                    button {
+                        class: "flex-1 py-2 rounded border border-zinc-700 text-zinc-400 text-xs font-bold hover:text-zinc-200 transition-all",
+                        onclick: move |_| on_close.call(()),
+                        "CANCEL"
+                    }
+                    button {
+                        class: "flex-1 py-2 bg-emerald-500 hover:bg-emerald-400 disabled:opacity-40 text-zinc-950 font-extrabold rounded text-xs tracking-wider uppercase transition-all",
+                        disabled: proposal.is_err(),
+                        onclick: move |_| {
+                            let Ok(durations) = proposal.clone() else { return };
+                            let fixed_flags = fixed();
+                            let paths = paths.clone();
+                            edit_items(formatter_items, history, format!("Fit {} entries to time", paths.len()), |items| {
+                                let order = EntryPath::all(items);
+                                let position = |path: &EntryPath| order.iter().position(|p| p == path);
+                                // With a known start, end times follow the new durations back to
+                                // back, as far as the selected entries run on from each other
+                                let mut running = start;
+                                let mut previous = None;
+                                for ((path, duration), fixed) in paths.iter().zip(durations).zip(fixed_flags) {
+                                    let pos = position(path);
+                                    if previous.is_some() && pos != previous.map(|p: usize| p + 1) {
+                                        running = None;
+                                    }
+                                    previous = pos;
+                                    let Some(entry) = path.entry_mut(items) else { continue };
+                                    entry.duration = duration;
+                                    entry.fixed = fixed;
+                                    if entry.locked {
+                                        // A locked end stays put; whatever follows starts from it
+                                        running = (!entry.end_time.is_midnight()).then_some(entry.end_time);
+                                    } else if let Some(at) = running {
+                                        let end = at.after(duration);
+                                        entry.end_time = end;
+                                        running = Some(end);
+                                    }
+                                }
+                            });
+                            on_close.call(());
+                        },
+                        "APPLY"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -3,22 +3,233 @@ use std::collections::HashMap;
 use crate::types::{
     AppSettings, PlaylistResponse, PlaylistItem, PlaylistInfo,
     OntimeEvent, OntimeEntry, OntimeRundown,
-    TimedEntry, FormatterItem, TimeEditContext, TimeField, InsertionMode,
+    TimedEntry, FormatterItem, TimeEditContext, TimeField, InsertionMode, InsertionPoint,
+    Duration, TimeOfDay, EditHistory, EntryPath, DragSource, DropTarget, apply_rules, matching_rule, RuleSet,
+    TimingHistory, ImportTable,
+    move_entry, anchor_conflicts, ripple_edit, item_use_counts, next_group_id, split_group, merge_with_next_group, ungroup,
 };
 use crate::utils::format_ms_to_duration;
-use crate::utils::parse_duration_to_ms;
-use crate::components::TimePicker;
+use crate::utils::parse_duration;
+use crate::components::{
+    edit_items, edit_items_coalesced, handle_history_keys, keep_text_undo, select_entry, ApplyTemplate, BulkEditBar,
+    DurationInput, FitDialog, HistoryBar, ImportDialog, LiveRebalance, ManualEntryForm, ProjectBar,
+    RehearsalPanel, SessionRecovery, TimePicker,
+};
+
+/// Rundown entry fields read into `OntimeEntry`'s own fields; everything else goes to `_extra`
+const MAPPED_ENTRY_FIELDS: [&str; 11] = [
+    "id", "type", "title", "cue", "note", "colour", "duration", "timeStart", "timeEnd", "dayOffset", "parent",
+];
+
+/// End time of the nearest entry or reference before the given position, in formatter order
+fn previous_end_time(items: &[FormatterItem], item_idx: usize, sub_item_idx: Option<usize>) -> Option<TimeOfDay> {
+    let end_of = |entry: &TimedEntry| (!entry.end_time.is_midnight()).then_some(entry.end_time);
+
+    if let (Some(sub_idx), Some(FormatterItem::Group { entries, .. })) = (sub_item_idx, items.get(item_idx)) {
+        if let Some(end) = entries.iter().take(sub_idx).rev().find_map(end_of) {
+            return Some(end);
+        }
+    }
+    items.iter().take(item_idx).rev().find_map(|item| match item {
+        FormatterItem::Standalone(entry) => end_of(entry),
+        FormatterItem::Group { entries, .. } => entries.iter().rev().find_map(end_of),
+        FormatterItem::Reference { time_end, .. } => (!time_end.is_midnight()).then_some(*time_end),
+        FormatterItem::Delay { .. } | FormatterItem::Milestone { .. } => None,
+    })
+}
+
+/// Put a new entry in the group a rule routed it to (created at the end if
+/// missing), else the selected group, else at the top level. `insertion_index`
+/// picks the position within that list; without it the entry goes at the end.
+fn place_entry(items: &mut Vec<FormatterItem>, entry: TimedEntry, rule_group: Option<String>, selected_group: Option<usize>) {
+    let target = match rule_group {
+        Some(group_name) => {
+            let existing = items.iter().position(|item| {
+                matches!(item, FormatterItem::Group { name, .. } if name.eq_ignore_ascii_case(&group_name))
+            });
+            Some(existing.unwrap_or_else(|| {
+                items.push(FormatterItem::Group {
+                    id: next_group_id(items),
+                    name: group_name,
+                    color: "#779BE7".to_string(),
+                    entries: vec![],
+                    collapsed: false,
+                    target_duration: None,
+                });
+                items.len() - 1
+            }))
+        }
+        None => selected_group.filter(|&idx| idx < items.len()),
+    };
+    // insertion_index is a one-off placement instruction, not kept on the entry
+    let mut entry = entry;
+    let at = entry.insertion_index.take();
+    match target {
+        Some(idx) => {
+            if let FormatterItem::Group { entries, .. } = &mut items[idx] {
+                let at = at.unwrap_or(entries.len()).min(entries.len());
+                entries.insert(at, entry);
+            }
+        }
+        None => {
+            let at = at.unwrap_or(items.len()).min(items.len());
+            items.insert(at, FormatterItem::Standalone(entry));
+        }
+    }
+}
+
+/// End time for a new entry: straight after the last reference, when that has a time
+fn suggested_end_time(items: &[FormatterItem], duration: Duration) -> TimeOfDay {
+    let last_ref = items.iter().rev().find_map(|item| match item {
+        FormatterItem::Reference { time_end, .. } => Some(*time_end),
+        _ => None,
+    });
+    match last_ref {
+        Some(ref_end) if !ref_end.is_midnight() => ref_end.after(duration),
+        _ => TimeOfDay::MIDNIGHT,
+    }
+}
+
+/// Ontime event body for a timed entry. Ontime's `dayOffset` is the day the
+/// event starts on, so an entry ending just after midnight may still start on
+/// the day before.
+fn event_payload(entry: &TimedEntry) -> serde_json::Value {
+    let mut payload = serde_json::json!(
+        { "type" : "event", "title" : entry.name, "duration" : entry.duration.as_ms(), "timeEnd" : entry
+        .end_time.ms_of_day(), "dayOffset" : entry.end_time.before(entry.duration).day_offset(), "countToEnd" :
+        entry.count_to_end, "linkStart" : entry.link_start }
+    );
+    if !entry.color.is_empty() {
+        payload["colour"] = serde_json::json!(entry.color);
+    }
+    if !entry.cue.is_empty() {
+        payload["cue"] = serde_json::json!(entry.cue);
+    }
+    if !entry.note.is_empty() {
+        payload["note"] = serde_json::json!(entry.note);
+    }
+    if entry.locked {
+        payload["timeStrategy"] = serde_json::json!("lock-end");
+    }
+    payload
+}
+
+/// POST one rundown entry, logging the outcome. Returns the id Ontime gave it.
+async fn post_entry(
+    client: &reqwest::Client,
+    endpoint: &str,
+    payload: &serde_json::Value,
+    mut logs: Signal<Vec<String>>,
+) -> Option<String> {
+    let timestamp = || chrono::Local::now().format("%H:%M:%S").to_string();
+    match client.post(endpoint).header("Content-Type", "application/json").json(payload).send().await {
+        Ok(resp) if resp.status().is_success() => {
+            let id = resp
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|j| j.get("id").and_then(|v| v.as_str()).map(|s| s.to_string()));
+            if let Some(ref id) = id {
+                logs.write().push(format!("[{}] ✓ Created: {}", timestamp(), id));
+            }
+            id
+        }
+        Ok(resp) => {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            logs.write().push(format!("[{}] ✗ Error {}: {}", timestamp(), status, body));
+            None
+        }
+        Err(e) => {
+            logs.write().push(format!("[{}] ✗ Request failed: {}", timestamp(), e));
+            None
+        }
+    }
+}
+
+/// Where hovering a top-level item drops: reordering takes the item's place,
+/// anything new goes in before it
+fn top_level_target(source: &DragSource, idx: usize) -> DropTarget {
+    match source {
+        DragSource::Item(_) => DropTarget::Item(idx),
+        _ => DropTarget::Gap(idx),
+    }
+}
+
+/// Which edge of a top-level item shows the drop line: Some(true) below, Some(false) above
+fn drop_indicator(dragged: &Option<DragSource>, over: &Option<DropTarget>, idx: usize) -> Option<bool> {
+    match (dragged, over) {
+        (Some(DragSource::Item(from)), Some(DropTarget::Item(to))) if *to == idx && *from != idx => Some(from < to),
+        (Some(_), Some(DropTarget::Gap(to))) if *to == idx => Some(false),
+        _ => None,
+    }
+}
+
+/// Where hovering inside a group drops: entries and standalone items go into
+/// the group, whole groups and references reorder around it
+fn group_target(items: &[FormatterItem], source: &DragSource, group_idx: usize, inner: DropTarget) -> DropTarget {
+    match source {
+        DragSource::Item(from) if !matches!(items.get(*from), Some(FormatterItem::Standalone(_))) => {
+            DropTarget::Item(group_idx)
+        }
+        _ => inner,
+    }
+}
+
+/// Whether this plan instance was pushed before and its event is still in the rundown.
+/// Matching on the instance rather than the title keeps reprises and manual
+/// entries that share a name with an existing event.
+fn already_pushed(existing_ids: &[String], entry: &TimedEntry) -> bool {
+    entry.ontime_id.as_ref().is_some_and(|id| existing_ids.contains(id))
+}
+
+/// Remember the Ontime ids of pushed instances on the plan, so pushing it again
+/// (e.g. after undoing the clear) doesn't duplicate them
+fn record_pushed_ids(items: &mut [FormatterItem], pushed: &std::collections::HashMap<String, String>) {
+    for path in EntryPath::all(items) {
+        if let Some(entry) = path.entry_mut(items) {
+            if let Some(id) = pushed.get(&entry.instance_id) {
+                entry.ontime_id = Some(id.clone());
+            }
+        }
+    }
+}
+
+/// An end time picked without a day offset lands after the previous end, so
+/// 00:30 following a 23:00 entry is read as past midnight
+fn roll_end_time(new_val: u64, previous_end: Option<TimeOfDay>) -> TimeOfDay {
+    let end_time = TimeOfDay::from_schedule_ms(new_val);
+    match previous_end {
+        Some(previous) => end_time.roll_forward_from(previous),
+        None => end_time,
+    }
+}
 
 #[component]
 pub fn Formatter() -> Element {
-    let mut playlist_name = use_signal(|| String::new());
+    let mut playlist_name = use_signal(String::new);
     // Unified list of items (standalone entries and groups)
-    let mut formatter_items = use_signal(|| Vec::<FormatterItem>::new());
+    let mut formatter_items = use_signal(Vec::<FormatterItem>::new);
     // None = standalone mode (append to end), Some(idx) = add inside group at that index
     let mut selected_group_idx = use_signal(|| Option::<usize>::None);
     let mut active_time_edit = use_signal(|| Option::<TimeEditContext>::None);
-    let mut dragged_item_idx = use_signal(|| Option::<usize>::None);
-    let mut drag_over_idx = use_signal(|| Option::<usize>::None);
+    // Project file backing the plan, and the items as they were last saved/opened
+    let project_path = use_signal(|| Option::<std::path::PathBuf>::None);
+    let saved_items = use_signal(Vec::<FormatterItem>::new);
+    // Undo/redo stack - every change to formatter_items goes through edit_items
+    let history = use_signal(EditHistory::default);
+    // Multi-selected entries for bulk edits, and the entry Shift+click extends from
+    let mut selection = use_signal(Vec::<EntryPath>::new);
+    let selection_anchor = use_signal(|| Option::<EntryPath>::None);
+    let mut selection_layout = use_signal(Vec::<(EntryPath, String)>::new);
+    let mut dragged = use_signal(|| Option::<DragSource>::None);
+    let mut drag_over = use_signal(|| Option::<DropTarget>::None);
+    let mut ref_cue = use_signal(String::new);
+    let mut ripple = use_signal(|| false);
+    let mut fit_paths = use_signal(|| Option::<Vec<EntryPath>>::None);
+    let mut import_table = use_signal(|| Option::<ImportTable>::None);
+    // Observed run times, used to suggest durations for new entries
+    let timing_history = use_signal(TimingHistory::load);
     let mut show_logs = use_signal(|| true);
     let mut logs = use_signal(|| vec![
         format!("[{}] System Ready", chrono::Local::now().format("%H:%M:%S"))
@@ -126,8 +337,18 @@ pub fn Formatter() -> Element {
                                         duration: entry_val.get("duration").and_then(|v| v.as_u64()).unwrap_or(0),
                                         time_start: entry_val.get("timeStart").and_then(|v| v.as_u64()).unwrap_or(0),
                                         time_end: entry_val.get("timeEnd").and_then(|v| v.as_u64()).unwrap_or(0),
+                                        day_offset: entry_val.get("dayOffset").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
                                         parent: entry_val.get("parent").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                                        _extra: HashMap::new(),
+                                        // Keep the rest (targetDuration, timeStrategy, skip, …) for capability checks
+                                        _extra: entry_val
+                                            .as_object()
+                                            .map(|obj| {
+                                                obj.iter()
+                                                    .filter(|(k, _)| !MAPPED_ENTRY_FIELDS.contains(&k.as_str()))
+                                                    .map(|(k, v)| (k.clone(), v.clone()))
+                                                    .collect()
+                                            })
+                                            .unwrap_or_default(),
                                     };
                                     entries_map.insert(entry_id.clone(), entry);
                                 }
@@ -178,52 +399,155 @@ pub fn Formatter() -> Element {
         formatter_items.read().iter().any(|item| match item {
             FormatterItem::Standalone(entry) => entry.item_id == item_id,
             FormatterItem::Group { entries, .. } => entries.iter().any(|e| e.item_id == item_id),
-            FormatterItem::Reference { .. } => false,
+            FormatterItem::Reference { .. } | FormatterItem::Delay { .. } | FormatterItem::Milestone { .. } => false,
         })
     };
     
-    // Add item - standalone or to selected group
-    let _add_item = move |item: &PlaylistItem| {
-        let entry = TimedEntry {
-            item_id: item.id.uuid.clone(),
-            name: item.id.name.clone(),
-            item_type: item.item_type.clone(),
-            duration: "00:05:00".to_string(),
-            end_time: "00:00:00".to_string(),
-            count_to_end: false,
-            link_start: true,
-            insertion_index: None,
+    // Add a playlist item - at a drop position, else where its rule or the selected group puts it
+    let mut add_playlist_item = move |item: PlaylistItem, target: Option<DropTarget>| {
+        let already_added = item_use_counts(&formatter_items.read()).get(&item.id.uuid).copied().unwrap_or(0);
+        if already_added > 0 {
+            logs.write()
+                .push(
+                    format!(
+                        "[{}] ⚠ {} is already in the plan - adding another instance",
+                        chrono::Local::now().format("%H:%M:%S"),
+                        item.id.name,
+                    ),
+                );
+        }
+
+        let settings = AppSettings::load();
+        let default_duration = match parse_duration(&settings.default_duration) {
+            Ok(d) => d,
+            Err(e) => {
+                logs.write()
+                    .push(
+                        format!(
+                            "[{}] ✗ Default duration \"{}\": {} - using 00:05:00",
+                            chrono::Local::now().format("%H:%M:%S"),
+                            settings.default_duration,
+                            e,
+                        ),
+                    );
+                Duration::from_ms(300_000)
+            }
         };
-        
-        let mut items = formatter_items.write();
-        
-        // Check if already added anywhere
-        let already_added = items.iter().any(|fi| match fi {
-            FormatterItem::Standalone(e) => e.item_id == item.id.uuid,
-            FormatterItem::Group { entries, .. } => entries.iter().any(|e| e.item_id == item.id.uuid),
-            FormatterItem::Reference { .. } => false,
+        // What this presentation usually runs for beats the generic default
+        let suggested_duration = timing_history
+            .read()
+            .lookup(item.presentation_uuid(), &item.id.name)
+            .map(|record| record.median())
+            .unwrap_or(default_duration);
+        let suggested_end_time = suggested_end_time(&formatter_items.read(), suggested_duration);
+        let mut entry = TimedEntry::from_playlist_item(&item, suggested_duration, suggested_end_time);
+        let matched = apply_rules(&RuleSet::new(&settings.rules), &item, &mut entry);
+        logs.write()
+            .push(
+                format!(
+                    "[{}] Added item: {}{}",
+                    chrono::Local::now().format("%H:%M:%S"),
+                    item.id.name,
+                    match &matched {
+                        Some((rule_idx, _)) => format!(" (rule {})", rule_idx + 1),
+                        None => String::new(),
+                    },
+                ),
+            );
+
+        // An explicit drop position wins over the rule's group
+        let (rule_group, group_idx) = match target {
+            None => (matched.and_then(|(_, group)| group), selected_group_idx()),
+            Some(DropTarget::Item(idx)) | Some(DropTarget::Gap(idx)) => {
+                entry.insertion_index = Some(idx);
+                (None, None)
+            }
+            Some(DropTarget::GroupEntry(idx, sub_idx)) => {
+                entry.insertion_index = Some(sub_idx);
+                (None, Some(idx))
+            }
+            Some(DropTarget::GroupEnd(idx)) => (None, Some(idx)),
+        };
+        edit_items(formatter_items, history, format!("Add {}", item.id.name), move |items| {
+            place_entry(items, entry, rule_group, group_idx);
         });
-        
-        if already_added {
-            return;
-        }
-        
-        match selected_group_idx() {
-            Some(idx) if idx < items.len() => {
-                // Add to selected group
-                if let FormatterItem::Group { entries, .. } = &mut items[idx] {
-                    entries.push(entry);
-                }
+    };
+
+    // Apply the pending drop. Drop events bubble out of nested zones, so later calls find nothing left to do.
+    let mut finish_drop = move || {
+        let (Some(source), Some(target)) = (dragged.take(), drag_over.take()) else { return };
+        // Follow the selected group by id, since moves shift top-level indices
+        let selected_group_id = selected_group_idx().and_then(|idx| match formatter_items.read().get(idx) {
+            Some(FormatterItem::Group { id, .. }) => Some(id.clone()),
+            _ => None,
+        });
+        match (source, target) {
+            (DragSource::Playlist(item), target) => add_playlist_item(item, Some(target)),
+            (DragSource::Entry(from), target) => {
+                edit_items(formatter_items, history, "Move entry", |items| move_entry(items, from, target));
             }
-            _ => {
-                // Add as standalone
-                items.push(FormatterItem::Standalone(entry));
+            (DragSource::Item(from), DropTarget::GroupEntry(..) | DropTarget::GroupEnd(_)) => {
+                edit_items(formatter_items, history, "Move into group", |items| {
+                    move_entry(items, EntryPath::standalone(from), target)
+                });
+            }
+            (DragSource::Item(from), DropTarget::Item(_) | DropTarget::Gap(_)) => {
+                let to = match target {
+                    // Taking the item out first shifts later gaps up by one
+                    DropTarget::Gap(gap) if from < gap => gap - 1,
+                    DropTarget::Item(to) | DropTarget::Gap(to) => to,
+                    _ => return,
+                };
+                if from == to || to >= formatter_items.read().len() {
+                    return;
+                }
+                edit_items(formatter_items, history, "Reorder items", |items| {
+                    let item = items.remove(from);
+                    items.insert(to, item);
+                });
             }
         }
+        if let Some(group_id) = selected_group_id {
+            let idx = formatter_items
+                .read()
+                .iter()
+                .position(|item| matches!(item, FormatterItem::Group { id, .. } if *id == group_id));
+            selected_group_idx.set(idx);
+        }
     };
 
+    // Paths go stale when entries move, so drop the selection whenever the layout changes
+    use_effect(move || {
+        let items = formatter_items.read();
+        let layout: Vec<(EntryPath, String)> = EntryPath::all(&items)
+            .into_iter()
+            .filter_map(|path| Some((path, path.entry(&items)?.instance_id.clone())))
+            .collect();
+        if layout != *selection_layout.peek() {
+            selection_layout.set(layout);
+            if !selection.peek().is_empty() {
+                selection.set(vec![]);
+            }
+        }
+    });
+
+    let use_counts = item_use_counts(&formatter_items.read());
+    let conflicts = anchor_conflicts(&formatter_items.read());
+    let show_drop_gaps = matches!(*dragged.read(), Some(DragSource::Playlist(_) | DragSource::Entry(_)));
+    let item_count = formatter_items.read().len();
+
+    // Name-pattern rules, for the matched-rule badges in the playlist list
+    let rules = use_hook(|| RuleSet::new(&AppSettings::load().rules));
+
     rsx! {
-        div { class: "h-full flex flex-col p-6 gap-6 overflow-hidden bg-zinc-950 font-mono",
+        div {
+            class: "h-full flex flex-col p-6 gap-6 overflow-hidden bg-zinc-950 font-mono outline-none",
+            tabindex: "0",
+            onkeydown: move |e| {
+                if handle_history_keys(&e, formatter_items, history, selected_group_idx) {
+                    e.prevent_default();
+                }
+            },
             // Connection Status Header
             div { class: "flex gap-4 shrink-0",
                 div { class: "flex items-center gap-3 text-[0.7rem] font-bold text-zinc-400 uppercase bg-zinc-900/50 py-2 px-3 rounded border border-zinc-800 shadow-sm",
@@ -246,6 +570,15 @@ pub fn Formatter() -> Element {
                 }
             }
 
+            SessionRecovery {
+                formatter_items,
+                history,
+                playlist_name,
+                project_path,
+                saved_items,
+                logs,
+            }
+
             div { class: "flex-1 min-h-0 w-full grid grid-cols-[320px_1fr_340px] gap-6",
                 // Left Panel - Playlist Source
                 div { class: "bg-zinc-900/80 border border-zinc-800/80 rounded-lg flex flex-col overflow-hidden shadow-lg backdrop-blur-sm",
@@ -296,110 +629,62 @@ pub fn Formatter() -> Element {
                                 "↻"
                             }
                         }
+                        ApplyTemplate {
+                            formatter_items,
+                            history,
+                            selected_group_idx,
+                            playlist_items: match &*playlist_resource.read() {
+                                Some(Ok(items)) => items.clone(),
+                                _ => vec![],
+                            },
+                            logs,
+                        }
                     }
                     div { class: "flex-1 overflow-y-auto p-2 space-y-1 scrollbar-thin scrollbar-thumb-zinc-700 scrollbar-track-transparent",
                         match &*playlist_resource.read() {
                             Some(Ok(items)) => rsx! {
-                                for item in items {
+                                for item in items.iter() {
                                     {
                                         let item_clone = item.clone();
-                                        let is_added = formatter_items
-                                            .read()
-                                            .iter()
-                                            .any(|fi| match fi {
-                                                FormatterItem::Standalone(e) => e.item_id == item.id.uuid,
-                                                FormatterItem::Group { entries, .. } => {
-                                                    entries.iter().any(|e| e.item_id == item.id.uuid)
-                                                }
-                                                FormatterItem::Reference { .. } => false,
-                                            });
+                                        let item_drag = item.clone();
+                                        let uses = use_counts.get(&item.id.uuid).copied().unwrap_or(0);
+                                        let is_added = uses > 0;
                                         rsx! {
                                             div {
                                                 class: if is_added { "flex items-center gap-3 p-2.5 rounded-md cursor-pointer transition-all border opacity-50 bg-emerald-500/5 border-emerald-500/20 hover:opacity-70" } else { "flex items-center gap-3 p-2.5 rounded-md cursor-pointer transition-all border border-transparent hover:bg-zinc-800 hover:border-zinc-700" },
-                                                onclick: move |_| {
-                                                    let suggested_end_time = {
-                                                        let items_read = formatter_items.read();
-                                                        let last_ref = items_read
-                                                            .iter()
-                                                            .rev()
-                                                            .find_map(|item| {
-                                                                if let FormatterItem::Reference { time_end, .. } = item {
-                                                                    Some(*time_end)
-                                                                } else {
-                                                                    None
-                                                                }
-                                                            });
-                                                        if let Some(ref_end) = last_ref {
-                                                            if ref_end > 0 {
-                                                                let settings = AppSettings::load();
-                                                                let default_duration_ms = parse_duration_to_ms(
-                                                                    &settings.default_duration,
-                                                                );
-                                                                format_ms_to_duration(ref_end + default_duration_ms)
-                                                            } else {
-                                                                "00:00:00".to_string()
-                                                            }
-                                                        } else {
-                                                            "00:00:00".to_string()
-                                                        }
-                                                    };
-                                                    let entry = TimedEntry {
-                                                        item_id: item_clone.id.uuid.clone(),
-                                                        name: item_clone.id.name.clone(),
-                                                        item_type: item_clone.item_type.clone(),
-                                                        duration: {
-                                                            match AppSettings::load().default_duration.as_str() {
-                                                                "" => "00:05:00".to_string(),
-                                                                s => s.to_string(),
-                                                            }
-                                                        },
-        
-                
-        
-                                                        end_time: { suggested_end_time },
-                                                        count_to_end: false,
-                                                        link_start: true,
-                                                        insertion_index: None,
-                                                    };
-                                                    let mut items = formatter_items.write();
-                                                    let already_added = items
-                                                        .iter()
-                                                        .any(|fi| match fi {
-                                                            FormatterItem::Standalone(e) => e.item_id == item_clone.id.uuid,
-                                                            FormatterItem::Group { entries, .. } => {
-                                                                entries.iter().any(|e| e.item_id == item_clone.id.uuid)
-                                                            }
-                                                            FormatterItem::Reference { .. } => false,
-                                                        });
-                
-
-                                                    if already_added {
-                                                        return;
-                                                    }
-                                                    logs.write()
-                                                        .push(
-                                                            format!(
-                                                                "[{}] Added item: {}",
-                                                                chrono::Local::now().format("%H:%M:%S"),
-                                                                item_clone.id.name,
-                                                            ),
-                                                        );
-                                                    match selected_group_idx() {
-                                                        Some(idx) if idx < items.len() => {
-                                                            if let FormatterItem::Group { entries, .. } = &mut items[idx] {
-                                                                entries.push(entry);
-                                                            }
-                                                        }
-                                                        _ => {
-                                                            items.push(FormatterItem::Standalone(entry));
-                                                        }
-                                                    }
+                                                draggable: true,
+                                                ondragstart: move |_| dragged.set(Some(DragSource::Playlist(item_drag.clone()))),
+                                                ondragend: move |_| {
+                                                    dragged.set(None);
+                                                    drag_over.set(None);
                                                 },
-                                                if is_added {
+                                                onclick: move |_| add_playlist_item(item_clone.clone(), None),
+                                                if uses > 1 {
+                                                    span {
+                                                        class: "h-5 px-1 flex items-center justify-center bg-amber-500 text-black rounded-full text-[0.65rem] font-extrabold shrink-0",
+                                                        title: "Planned {uses} times",
+                                                        "×{uses}"
+                                                    }
+                                                } else if is_added {
                                                     span { class: "w-5 h-5 flex items-center justify-center bg-emerald-500 text-black rounded-full text-[0.65rem] font-extrabold shrink-0", "✓" }
                                                 }
                                                 span { class: "text-zinc-600 text-[0.7rem] font-mono min-w-[20px] text-right", "{item.id.index + 1}" }
                                                 span { class: "flex-1 truncate text-sm text-zinc-200", "{item.id.name}" }
+                                                if let Some(record) = timing_history.read().lookup(item.presentation_uuid(), &item.id.name) {
+                                                    span {
+                                                        class: "text-[0.6rem] font-mono text-zinc-400 bg-zinc-800 px-1.5 rounded shrink-0",
+                                                        title: "{record.summary()}",
+                                                        "~{record.median()}"
+                                                    }
+                                                }
+                                                if let Some((rule_idx, rule)) = matching_rule(&rules, item) {
+                                                    span {
+                                                        class: "text-[0.6rem] font-bold text-cyan-500 bg-cyan-500/10 border border-cyan-500/20 px-1.5 rounded shrink-0",
+                                                        style: if !rule.color.is_empty() { format!("border-left: 3px solid {};", rule.color) } else { String::new() },
+                                                        title: "{rule.summary()}",
+                                                        "R{rule_idx + 1}"
+                                                    }
+                                                }
                                                 span { class: "text-[0.6rem] text-zinc-500 uppercase tracking-wider", "{item.item_type}" }
                                             }
                                         }
@@ -417,7 +702,34 @@ pub fn Formatter() -> Element {
                 }
                 // Right Panel - Formatter Groups
                 div { class: "bg-zinc-900/80 border border-zinc-800/80 rounded-lg flex flex-col overflow-hidden shadow-lg backdrop-blur-sm",
-                    div { class: "p-4 text-xs font-extrabold tracking-widest text-zinc-500 border-b border-zinc-800/80 bg-zinc-950/30 uppercase flex justify-between items-center", "ONTIME FORMATTER" }
+                    div { class: "p-4 text-xs font-extrabold tracking-widest text-zinc-500 border-b border-zinc-800/80 bg-zinc-950/30 uppercase flex justify-between items-center", 
+                        span { "ONTIME FORMATTER" }
+                        button {
+                            class: if ripple() { "px-2 py-1 rounded border border-amber-500/50 text-amber-500 bg-amber-500/10 text-[0.65rem] transition-all" } else { "px-2 py-1 rounded border border-zinc-700 text-zinc-500 text-[0.65rem] hover:text-zinc-300 transition-all" },
+                            title: "Ripple: time changes shift the following end times up to the next locked entry",
+                            onclick: move |_| ripple.set(!ripple()),
+                            if ripple() { "RIPPLE ON" } else { "RIPPLE OFF" }
+                        }
+                    }
+                    ProjectBar {
+                        formatter_items,
+                        history,
+                        selected_group_idx,
+                        playlist_name,
+                        project_path,
+                        saved_items,
+                        playlist_uuid: match &*playlists_resource.read() {
+                            Some(Ok(playlists)) => playlists
+                                .iter()
+                                .find(|pl| pl.id.name == playlist_name())
+                                .map(|pl| pl.id.uuid.clone()),
+                            _ => None,
+                        },
+                        import_table,
+                        logs,
+                    }
+                    HistoryBar { formatter_items, history, selected_group_idx }
+                    BulkEditBar { formatter_items, history, selection, fit_paths, ripple }
                     // Insertion selector removed - replaced by Reference Items list logic
                     div { class: "insertion-selector",
                         div { style: "color: var(--text-muted); font-size: 0.8rem; padding: 0 0 10px 0;",
@@ -425,76 +737,139 @@ pub fn Formatter() -> Element {
                         }
                     }
                     // Timeline Panel - removed placeholder
+                    ManualEntryForm {
+                        on_add: move |(mut entry, end_time): (TimedEntry, Option<TimeOfDay>)| {
+                            entry.end_time = end_time.unwrap_or_else(|| suggested_end_time(&formatter_items.read(), entry.duration));
+                            logs.write()
+                                .push(
+                                    format!(
+                                        "[{}] Added manual entry: {}",
+                                        chrono::Local::now().format("%H:%M:%S"),
+                                        entry.name,
+                                    ),
+                                );
+                            let group_idx = selected_group_idx();
+                            edit_items(formatter_items, history, format!("Add {}", entry.name), move |items| {
+                                place_entry(items, entry, None, group_idx);
+                            });
+                        },
+                    }
                     button {
                         class: "m-4 p-3 border-2 border-dashed border-zinc-700 rounded-lg text-zinc-500 font-mono text-xs font-bold hover:border-emerald-500/50 hover:text-emerald-500 hover:bg-emerald-500/5 transition-all w-[calc(100%-2rem)] cursor-pointer",
                         onclick: move |_| {
-                            let mut items = formatter_items.write();
-                            let group_count = items
+                            let group_count = formatter_items
+                                .read()
                                 // Auto-select new group
                                 .iter()
                                 .filter(|i| matches!(i, FormatterItem::Group { .. }))
                                 .count();
-                            let new_id = format!("group-{}", group_count + 1);
-                            items
-                                .push(FormatterItem::Group {
-                                    id: new_id,
-                                    name: format!("GROUP {}", group_count + 1),
-                                    color: "#779BE7".to_string(),
-                                    entries: vec![],
-                                    collapsed: false,
-                                });
-                            selected_group_idx.set(Some(items.len() - 1));
+                            let new_id = next_group_id(&formatter_items.read());
+                            edit_items(formatter_items, history, format!("New group {}", group_count + 1), |items| {
+                                items
+                                    .push(FormatterItem::Group {
+                                        id: new_id,
+                                        name: format!("GROUP {}", group_count + 1),
+                                        color: "#779BE7".to_string(),
+                                        entries: vec![],
+                                        collapsed: false,
+                                        target_duration: None,
+                                    });
+                            });
+                            selected_group_idx.set(Some(formatter_items.read().len() - 1));
                         },
                         "+ NEW GROUP"
                     }
+                    div { class: "mx-4 mb-4 grid grid-cols-2 gap-2",
+                        button {
+                            class: "p-2 border border-dashed border-zinc-700 rounded-lg text-zinc-500 font-mono text-[0.65rem] font-bold hover:border-amber-500/50 hover:text-amber-500 hover:bg-amber-500/5 transition-all cursor-pointer",
+                            title: "Planned delay block",
+                            onclick: move |_| {
+                                edit_items(formatter_items, history, "Add delay", |items| {
+                                    items.push(FormatterItem::new_delay(Duration::from_ms(300_000)));
+                                });
+                            },
+                            "+ DELAY"
+                        }
+                        button {
+                            class: "p-2 border border-dashed border-zinc-700 rounded-lg text-zinc-500 font-mono text-[0.65rem] font-bold hover:border-emerald-500/50 hover:text-emerald-500 hover:bg-emerald-500/5 transition-all cursor-pointer",
+                            title: "Milestone marker, e.g. Doors open",
+                            onclick: move |_| {
+                                edit_items(formatter_items, history, "Add milestone", |items| {
+                                    items.push(FormatterItem::new_milestone("Milestone"));
+                                });
+                            },
+                            "+ MILESTONE"
+                        }
+                    }
                     div { class: "flex-1 overflow-y-auto px-4 pb-4 space-y-3 scrollbar-thin scrollbar-thumb-zinc-700 scrollbar-track-transparent",
                         // Render all items (standalone and groups) in unified list
                         for (item_idx, formatter_item) in formatter_items.read().iter().enumerate() {
+                            // Slot between top-level items for new entries dragged in from the playlist
+                            if show_drop_gaps {
+                                div {
+                                    class: if *drag_over.read() == Some(DropTarget::Gap(item_idx)) { "drop-gap active" } else { "drop-gap" },
+                                    ondragenter: move |e| {
+                                        e.prevent_default();
+                                        drag_over.set(Some(DropTarget::Gap(item_idx)));
+                                    },
+                                    ondragover: move |e| e.prevent_default(),
+                                    ondrop: move |e| {
+                                        e.prevent_default();
+                                        finish_drop();
+                                    },
+                                }
+                            }
                             {
                                 // Drag handlers
                                 // We need to capture the current item_idx for the closures
                                 let current_idx = item_idx;
                                 
                                 // Determine if this item is being dragged to add styling class
-                                let is_dragging = *dragged_item_idx.read() == Some(current_idx);
+                                let is_dragging = *dragged.read() == Some(DragSource::Item(current_idx));
                                 
                                 match formatter_item {
                                 FormatterItem::Standalone(entry) => {
-                                    let duration_clone = entry.duration.clone();
-                                    let end_time_clone = entry.end_time.clone();
+                                    let instance_id = entry.instance_id.clone();
+                                    let duration_clone = entry.duration;
+                                    let end_time_clone = entry.end_time;
                                     let count_to_end = entry.count_to_end;
                                     let link_start = entry.link_start;
-                                    // Calculate drag over class
-                                    let drag_over_class = if let (Some(dragged_idx), Some(over_idx)) = (*dragged_item_idx.read(), *drag_over_idx.read()) {
-                                        if over_idx == current_idx && dragged_idx != current_idx {
-                                            if dragged_idx < current_idx {
-                                                " drag-over-bottom"
-                                            } else {
-                                                " drag-over-top"
-                                            }
-                                        } else {
-                                            ""
-                                        }
+                                    let locked = entry.locked;
+                                    let fixed = entry.fixed;
+                                    let history_tip = timing_history.read().lookup(entry.presentation_uuid.as_deref(), &entry.name).map(|r| r.summary()).unwrap_or_default();
+                                    let selected_class = if selection.read().contains(&EntryPath::standalone(item_idx)) {
+                                        " !border-cyan-500 !bg-cyan-500/10"
                                     } else {
                                         ""
                                     };
+                                    // Calculate drag over class
+                                    let drag_over_class = match drop_indicator(&dragged.read(), &drag_over.read(), current_idx) {
+                                        Some(true) => " drag-over-bottom",
+                                        Some(false) => " drag-over-top",
+                                        None => "",
+                                    };
 
                                     rsx! {
                                         div { 
                                             class: if is_dragging { 
-                                                format!("flex flex-col items-stretch p-3 rounded-md bg-zinc-950/50 border border-zinc-800/50 mb-1 backdrop-blur-sm cursor-grab active:cursor-grabbing dragging{}", drag_over_class)
+                                                format!("flex flex-col items-stretch p-3 rounded-md bg-zinc-950/50 border border-zinc-800/50 mb-1 backdrop-blur-sm cursor-grab active:cursor-grabbing draggable dragging{}{}", drag_over_class, selected_class)
                                             } else { 
-                                                format!("flex flex-col items-stretch p-3 rounded-md bg-zinc-950/50 border border-zinc-800/50 mb-1 backdrop-blur-sm cursor-grab active:cursor-grabbing{}", drag_over_class)
+                                                format!("flex flex-col items-stretch p-3 rounded-md bg-zinc-950/50 border border-zinc-800/50 mb-1 backdrop-blur-sm cursor-grab active:cursor-grabbing draggable{}{}", drag_over_class, selected_class)
                                             },
+                                            style: if !entry.color.is_empty() { format!("border-left: 3px solid {};", entry.color) } else { String::new() },
                                             draggable: true,
                                             ondragstart: move |_| {
-                                                dragged_item_idx.set(Some(current_idx));
+                                                dragged.set(Some(DragSource::Item(current_idx)));
+                                            },
+                                            ondragend: move |_| {
+                                                dragged.set(None);
+                                                drag_over.set(None);
                                             },
                                             ondragenter: move |e| {
                                                 e.prevent_default();
                                                 // Only set if we are dragging something
-                                                if dragged_item_idx.read().is_some() {
-                                                    drag_over_idx.set(Some(current_idx));
+                                                if let Some(source) = dragged.read().as_ref() {
+                                                    drag_over.set(Some(top_level_target(source, current_idx)));
                                                 }
                                             },
                                             ondragover: move |e| {
@@ -502,24 +877,58 @@ pub fn Formatter() -> Element {
                                             },
                                             ondrop: move |e| {
                                                 e.prevent_default();
-                                                let source_idx_opt = *dragged_item_idx.read();
-                                                if let Some(source_idx) = source_idx_opt {
-                                                    if source_idx != current_idx {
-                                                        let mut items = formatter_items.write();
-                                                        let item = items.remove(source_idx);
-                                                        items.insert(current_idx, item);
-                                                    }
-                                                }
-                                                dragged_item_idx.set(None);
-                                                drag_over_idx.set(None);
+                                                finish_drop();
                                             },
-                                            div { class: "flex items-center mb-2.5",
+                                            div {
+                                                class: "flex items-center mb-2.5 cursor-pointer",
+                                                title: "Click to select, Ctrl/Shift+click to select several",
+                                                onclick: move |e| {
+                                                    select_entry(selection, selection_anchor, &formatter_items.read(), EntryPath::standalone(item_idx), e.modifiers());
+                                                },
                                                 span { class: "text-zinc-600 cursor-grab mr-2 select-none font-bold", "⋮⋮" }
-                                                span { class: "flex-1 text-sm text-zinc-200 truncate", "{entry.name}" }
+                                                if entry.manual {
+                                                    input {
+                                                        class: "flex-1 min-w-0 bg-transparent border border-transparent text-sm text-zinc-200 px-1 rounded hover:border-zinc-700 focus:outline-none focus:border-cyan-500",
+                                                        value: "{entry.name}",
+                                                        onkeydown: keep_text_undo,
+                                                        onclick: move |e| e.stop_propagation(),
+                                                        oninput: move |e| {
+                                                            edit_items_coalesced(formatter_items, history, "Rename entry", instance_id.clone(), |items| {
+                                                                if let Some(FormatterItem::Standalone(ent)) = items.get_mut(item_idx) {
+                                                                    ent.name = e.value();
+                                                                }
+                                                            });
+                                                        },
+                                                    }
+                                                    span {
+                                                        class: "text-[0.6rem] font-bold text-emerald-500 bg-emerald-500/10 border border-emerald-500/20 px-1.5 rounded shrink-0 mr-1",
+                                                        title: "Manual entry - not from ProPresenter",
+                                                        "MANUAL"
+                                                    }
+                                                } else {
+                                                    span { class: "flex-1 text-sm text-zinc-200 truncate", "{entry.name}" }
+                                                }
+                                                if let Some(overrun) = conflicts.get(&entry.instance_id) {
+                                                    span {
+                                                        class: "text-[0.6rem] font-bold text-red-500 bg-red-500/10 border border-red-500/30 px-1.5 rounded shrink-0 mr-1",
+                                                        title: "Locked end time can't be met: the entry before runs {overrun} into this one",
+                                                        "⚠ +{overrun}"
+                                                    }
+                                                }
+                                                if let Some(&uses) = use_counts.get(&entry.item_id).filter(|&&n| n > 1) {
+                                                    span {
+                                                        class: "text-[0.6rem] font-bold text-amber-500 bg-amber-500/10 border border-amber-500/20 px-1.5 rounded shrink-0 mr-1",
+                                                        title: "This ProPresenter item is planned {uses} times",
+                                                        "⚠ ×{uses}"
+                                                    }
+                                                }
                                                 button {
                                                     class: "w-7 h-7 flex items-center justify-center rounded text-zinc-500 hover:bg-red-500/10 hover:border-red-500/30 hover:text-red-500 transition-all font-mono text-lg",
-                                                    onclick: move |_| {
-                                                        formatter_items.write().remove(item_idx);
+                                                    onclick: move |e| {
+                                                        e.stop_propagation();
+                                                        edit_items(formatter_items, history, "Remove item", |items| {
+                                                            items.remove(item_idx);
+                                                        });
                                                     },
                                                     "×"
                                                 }
@@ -531,7 +940,9 @@ pub fn Formatter() -> Element {
                                                         r#type: "text",
                                                         class: "w-20 p-1.5 rounded bg-zinc-900 border border-zinc-800 text-zinc-200 font-mono text-xs text-center focus:border-cyan-500 focus:outline-none cursor-pointer",
                                                         value: "{duration_clone}",
+                                                        title: "{history_tip}",
                                                         readonly: true,
+                                                        onkeydown: keep_text_undo,
                                                         onclick: move |_| {
                                                             active_time_edit
                                                                 .set(
@@ -539,7 +950,7 @@ pub fn Formatter() -> Element {
                                                                         item_idx,
                                                                         sub_item_idx: None,
                                                                         field: TimeField::Duration,
-                                                                        current_value: duration_clone.clone(),
+                                                                        current_value: duration_clone.as_ms(),
                                                                     }),
                                                                 );
                                                         },
@@ -552,6 +963,7 @@ pub fn Formatter() -> Element {
                                                         class: "w-20 p-1.5 rounded bg-zinc-900 border border-zinc-800 text-zinc-200 font-mono text-xs text-center focus:border-cyan-500 focus:outline-none cursor-pointer",
                                                         value: "{end_time_clone}",
                                                         readonly: true,
+                                                        onkeydown: keep_text_undo,
                                                         onclick: move |_| {
                                                             active_time_edit
                                                                 .set(
@@ -559,7 +971,7 @@ pub fn Formatter() -> Element {
                                                                         item_idx,
                                                                         sub_item_idx: None,
                                                                         field: TimeField::EndTime,
-                                                                        current_value: end_time_clone.clone(),
+                                                                        current_value: end_time_clone.schedule_ms(),
                                                                     }),
                                                                 );
                                                         },
@@ -572,11 +984,11 @@ pub fn Formatter() -> Element {
                                                         class: "w-4 h-4 rounded border-zinc-600 bg-zinc-800 text-cyan-500 focus:ring-0 focus:ring-offset-0 cursor-pointer",
                                                         checked: count_to_end,
                                                         onchange: move |e| {
-                                                            if let FormatterItem::Standalone(ref mut ent) = &mut formatter_items
-                                                                .write()[item_idx]
-                                                            {
-                                                                ent.count_to_end = e.checked();
-                                                            }
+                                                            edit_items(formatter_items, history, "Toggle count to end", |items| {
+                                                                if let FormatterItem::Standalone(ent) = &mut items[item_idx] {
+                                                                    ent.count_to_end = e.checked();
+                                                                }
+                                                            });
                                                         },
                                                     }
                                                     label { r#for: "cte-{item_idx}", class: "text-xs text-zinc-400 font-bold cursor-pointer", "CTE" }
@@ -588,53 +1000,92 @@ pub fn Formatter() -> Element {
                                                         class: "w-4 h-4 rounded border-zinc-600 bg-zinc-800 text-cyan-500 focus:ring-0 focus:ring-offset-0 cursor-pointer",
                                                         checked: link_start,
                                                         onchange: move |e| {
-                                                            if let FormatterItem::Standalone(ref mut ent) = &mut formatter_items
-                                                                .write()[item_idx]
-                                                            {
-                                                                ent.link_start = e.checked();
-                                                            }
+                                                            edit_items(formatter_items, history, "Toggle link start", |items| {
+                                                                if let FormatterItem::Standalone(ent) = &mut items[item_idx] {
+                                                                    ent.link_start = e.checked();
+                                                                }
+                                                            });
                                                         },
                                                     }
                                                     label { r#for: "ls-{item_idx}", class: "text-xs text-zinc-400 font-bold cursor-pointer", "Link" }
                                                 }
+                                                div { class: "flex items-center gap-1.5",
+                                                    input {
+                                                        r#type: "checkbox",
+                                                        id: "lock-{item_idx}",
+                                                        class: "w-4 h-4 rounded border-zinc-600 bg-zinc-800 text-cyan-500 focus:ring-0 focus:ring-offset-0 cursor-pointer",
+                                                        checked: locked,
+                                                        onchange: move |e| {
+                                                            edit_items(formatter_items, history, "Toggle locked end", |items| {
+                                                                if let FormatterItem::Standalone(ent) = &mut items[item_idx] {
+                                                                    ent.locked = e.checked();
+                                                                }
+                                                            });
+                                                        },
+                                                    }
+                                                    label { r#for: "lock-{item_idx}", class: "text-xs text-zinc-400 font-bold cursor-pointer", title: "Locked end time - ripple edits stop here", "Lock" }
+                                                }
+                                                div { class: "flex items-center gap-1.5",
+                                                    input {
+                                                        r#type: "checkbox",
+                                                        id: "fixed-{item_idx}",
+                                                        class: "w-4 h-4 rounded border-zinc-600 bg-zinc-800 text-cyan-500 focus:ring-0 focus:ring-offset-0 cursor-pointer",
+                                                        checked: fixed,
+                                                        onchange: move |e| {
+                                                            edit_items(formatter_items, history, "Toggle fixed duration", |items| {
+                                                                if let FormatterItem::Standalone(ent) = &mut items[item_idx] {
+                                                                    ent.fixed = e.checked();
+                                                                }
+                                                            });
+                                                        },
+                                                    }
+                                                    label { r#for: "fixed-{item_idx}", class: "text-xs text-zinc-400 font-bold cursor-pointer", title: "Fixed duration - fit-to-time leaves it alone", "Fixed" }
+                                                }
                                             }
                                         }
                                     }
                                 }
-                                FormatterItem::Group { id: _, name, color, entries, collapsed: _ } => {
+                                FormatterItem::Group { id, name, color, entries, collapsed, target_duration } => {
+                                    // Each handler below owns a copy of the id for its undo coalescing key
+                                    let (name_key, color_key, budget_key) = (id.clone(), id.clone(), id.clone());
+                                    let is_collapsed = *collapsed;
+                                    let budget = *target_duration;
+                                    let planned_ms: u64 = entries.iter().map(|e| e.duration.as_ms()).sum();
                                     let name_clone = name.clone();
                                     let color_clone = color.clone();
                                     let entry_count = entries.len();
-                                    // Calculate drag over class
-                                    let drag_over_class = if let (Some(dragged_idx), Some(over_idx)) = (*dragged_item_idx.read(), *drag_over_idx.read()) {
-                                        if over_idx == current_idx && dragged_idx != current_idx {
-                                            if dragged_idx < current_idx {
-                                                " drag-over-bottom"
-                                            } else {
-                                                " drag-over-top"
-                                            }
-                                        } else {
-                                            ""
-                                        }
+                                    let next_is_group = matches!(formatter_items.read().get(item_idx + 1), Some(FormatterItem::Group { .. }));
+                                    let drop_into_class = if *drag_over.read() == Some(DropTarget::GroupEnd(item_idx)) {
+                                        " drag-over-into"
                                     } else {
                                         ""
                                     };
+                                    // Calculate drag over class
+                                    let drag_over_class = match drop_indicator(&dragged.read(), &drag_over.read(), current_idx) {
+                                        Some(true) => " drag-over-bottom",
+                                        Some(false) => " drag-over-top",
+                                        None => "",
+                                    };
 
                                     rsx! {
                                         div {
                                             class: if selected_group_idx() == Some(item_idx) { 
-                                                format!("bg-zinc-950 border border-zinc-800 rounded-lg mb-3 cursor-pointer transition-all overflow-hidden !border-cyan-500 shadow-[0_0_0_1px_#06b6d4,0_0_20px_rgba(6,182,212,0.1)] draggable{}", drag_over_class)
+                                                format!("bg-zinc-950 border border-zinc-800 rounded-lg mb-3 cursor-pointer transition-all overflow-hidden !border-cyan-500 shadow-[0_0_0_1px_#06b6d4,0_0_20px_rgba(6,182,212,0.1)] draggable{}{}", drag_over_class, drop_into_class)
                                             } else { 
-                                                format!("bg-zinc-950 border border-zinc-800 rounded-lg mb-3 cursor-pointer transition-all overflow-hidden hover:border-zinc-600 draggable{}", drag_over_class)
+                                                format!("bg-zinc-950 border border-zinc-800 rounded-lg mb-3 cursor-pointer transition-all overflow-hidden hover:border-zinc-600 draggable{}{}", drag_over_class, drop_into_class)
                                             },
                                             draggable: true,
                                             ondragstart: move |_| {
-                                                dragged_item_idx.set(Some(current_idx));
+                                                dragged.set(Some(DragSource::Item(current_idx)));
+                                            },
+                                            ondragend: move |_| {
+                                                dragged.set(None);
+                                                drag_over.set(None);
                                             },
                                             ondragenter: move |e| {
                                                 e.prevent_default();
-                                                if dragged_item_idx.read().is_some() {
-                                                    drag_over_idx.set(Some(current_idx));
+                                                if let Some(source) = dragged.read().as_ref() {
+                                                    drag_over.set(Some(top_level_target(source, current_idx)));
                                                 }
                                             },
                                             ondragover: move |e| {
@@ -642,23 +1093,7 @@ pub fn Formatter() -> Element {
                                             },
                                             ondrop: move |e| {
                                                 e.prevent_default();
-                                                let source_idx_opt = *dragged_item_idx.read();
-                                                if let Some(source_idx) = source_idx_opt {
-                                                    if source_idx != current_idx {
-                                                        let mut items = formatter_items.write();
-                                                        let item = items.remove(source_idx);
-                                                        items.insert(current_idx, item);
-                                                        
-                                                        // Update selection if we moved the selected group
-                                                        if selected_group_idx() == Some(source_idx) {
-                                                            selected_group_idx.set(Some(current_idx));
-                                                        } else if selected_group_idx() == Some(current_idx) {
-                                                            selected_group_idx.set(None);
-                                                        }
-                                                    }
-                                                }
-                                                dragged_item_idx.set(None);
-                                                drag_over_idx.set(None);
+                                                finish_drop();
                                             },
                                             onclick: move |_| {
                                                 if selected_group_idx() == Some(item_idx) {
@@ -667,17 +1102,42 @@ pub fn Formatter() -> Element {
                                                     selected_group_idx.set(Some(item_idx));
                                                 }
                                             },
-                                            div { class: "flex justify-between items-center p-4 border-b border-zinc-800 bg-zinc-900/50",
+                                            div {
+                                                class: "flex justify-between items-center p-4 border-b border-zinc-800 bg-zinc-900/50",
+                                                // New entries dropped on the header go to the end of the group
+                                                ondragenter: move |e| {
+                                                    e.prevent_default();
+                                                    e.stop_propagation();
+                                                    if let Some(source) = dragged.read().as_ref() {
+                                                        drag_over.set(Some(group_target(&formatter_items.read(), source, item_idx, DropTarget::GroupEnd(item_idx))));
+                                                    }
+                                                },
                                                 span { class: "text-zinc-600 cursor-grab mr-2 select-none font-bold", "⋮⋮" }
+                                                button {
+                                                    class: "w-6 h-6 flex items-center justify-center rounded text-zinc-500 hover:text-zinc-200 hover:bg-zinc-800 transition-all mr-1",
+                                                    title: if is_collapsed { "Expand group" } else { "Collapse group" },
+                                                    onclick: move |e| {
+                                                        e.stop_propagation();
+                                                        let label = if is_collapsed { "Expand group" } else { "Collapse group" };
+                                                        edit_items(formatter_items, history, label, |items| {
+                                                            if let FormatterItem::Group { collapsed, .. } = &mut items[item_idx] {
+                                                                *collapsed = !is_collapsed;
+                                                            }
+                                                        });
+                                                    },
+                                                    if is_collapsed { "▸" } else { "▾" }
+                                                }
                                                 input {
                                                     class: "bg-transparent border border-transparent text-zinc-100 font-mono font-bold text-sm px-2 py-1 rounded flex-1 min-w-0 hover:bg-zinc-800 hover:border-zinc-700 focus:outline-none focus:bg-zinc-950 focus:border-cyan-500 focus:shadow-[0_0_0_2px_rgba(6,182,212,0.1)] transition-all",
                                                     value: "{name_clone}",
+                                                    onkeydown: keep_text_undo,
                                                     onclick: move |e| e.stop_propagation(),
                                                     oninput: move |e| {
-                                                        if let FormatterItem::Group { name, .. } = &mut formatter_items.write()[item_idx]
-                                                        {
-                                                            *name = e.value();
-                                                        }
+                                                        edit_items_coalesced(formatter_items, history, "Rename group", name_key.clone(), |items| {
+                                                            if let FormatterItem::Group { name, .. } = &mut items[item_idx] {
+                                                                *name = e.value();
+                                                            }
+                                                        });
                                                     },
                                                 }
                                                 div { style: "width: 30px; height: 30px; border-radius: 4px; margin-right: 8px; background-color: {color_clone}; border: 1px solid rgba(255,255,255,0.3);" }
@@ -686,45 +1146,197 @@ pub fn Formatter() -> Element {
                                                     style: "width: 80px; padding: 4px 8px; border-radius: 4px; border: 1px solid rgba(255,255,255,0.2); background: rgba(0,0,0,0.3); color: white; font-family: monospace;",
                                                     value: "{color_clone}",
                                                     placeholder: "#779BE7",
+                                                    onkeydown: keep_text_undo,
                                                     onclick: move |e| e.stop_propagation(),
                                                     oninput: move |e| {
-                                                        if let FormatterItem::Group { color, .. } = &mut formatter_items
-                                                            .write()[item_idx]
-                                                        {
-                                                            *color = e.value();
-                                                        }
+                                                        edit_items_coalesced(formatter_items, history, "Change group colour", color_key.clone(), |items| {
+                                                            if let FormatterItem::Group { color, .. } = &mut items[item_idx] {
+                                                                *color = e.value();
+                                                            }
+                                                        });
                                                     },
                                                 }
                                                 span { class: "text-xs text-zinc-500 ml-2", "{entry_count} items" }
+                                                div { class: "w-20 ml-2 shrink-0", onclick: move |e| e.stop_propagation(),
+                                                    DurationInput {
+                                                        value: budget,
+                                                        placeholder: "budget",
+                                                        on_change: move |d: Option<Duration>| {
+                                                            edit_items_coalesced(formatter_items, history, "Set group budget", budget_key.clone(), |items| {
+                                                                if let FormatterItem::Group { target_duration, .. } = &mut items[item_idx] {
+                                                                    *target_duration = d;
+                                                                }
+                                                            });
+                                                        },
+                                                    }
+                                                }
+                                                if let Some(budget) = budget {
+                                                    {
+                                                        let planned = Duration::from_ms(planned_ms);
+                                                        let (class, diff) = match planned_ms.cmp(&budget.as_ms()) {
+                                                            std::cmp::Ordering::Greater => ("text-red-500", format!("+{}", Duration::from_ms(planned_ms - budget.as_ms()))),
+                                                            std::cmp::Ordering::Less => ("text-amber-500", format!("-{}", Duration::from_ms(budget.as_ms() - planned_ms))),
+                                                            std::cmp::Ordering::Equal => ("text-emerald-500", "±0".to_string()),
+                                                        };
+                                                        rsx! {
+                                                            span {
+                                                                class: "text-[0.65rem] font-mono ml-2 whitespace-nowrap text-zinc-500",
+                                                                title: "Planned {planned} of a {budget} budget",
+                                                                "{planned} / {budget} "
+                                                                span { class: "font-bold {class}", "{diff}" }
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                                button {
+                                                    class: "w-7 h-7 flex items-center justify-center rounded text-zinc-500 hover:bg-zinc-800 hover:text-cyan-500 transition-all text-sm ml-2",
+                                                    title: "Fit the group to a target end time or length",
+                                                    onclick: move |e| {
+                                                        e.stop_propagation();
+                                                        fit_paths.set(Some((0..entry_count).map(|i| EntryPath::in_group(item_idx, i)).collect()));
+                                                    },
+                                                    "⇔"
+                                                }
+                                                if next_is_group {
+                                                    button {
+                                                        class: "w-7 h-7 flex items-center justify-center rounded text-zinc-500 hover:bg-zinc-800 hover:text-cyan-500 transition-all text-sm ml-2",
+                                                        title: "Merge with the group below",
+                                                        onclick: move |e| {
+                                                            e.stop_propagation();
+                                                            edit_items(formatter_items, history, "Merge groups", |items| merge_with_next_group(items, item_idx));
+                                                            selected_group_idx.set(Some(item_idx));
+                                                        },
+                                                        "⤓"
+                                                    }
+                                                }
+                                                button {
+                                                    class: "w-7 h-7 flex items-center justify-center rounded text-zinc-500 hover:bg-zinc-800 hover:text-cyan-500 transition-all text-sm ml-2",
+                                                    title: "Ungroup - keep the entries as standalone items",
+                                                    onclick: move |e| {
+                                                        e.stop_propagation();
+                                                        edit_items(formatter_items, history, "Ungroup", |items| ungroup(items, item_idx));
+                                                        selected_group_idx.set(None);
+                                                    },
+                                                    "⇱"
+                                                }
                                                 button {
                                                     class: "w-7 h-7 flex items-center justify-center rounded text-zinc-500 hover:bg-red-500/10 hover:border-red-500/30 hover:text-red-500 transition-all font-mono text-lg ml-2",
                                                     title: "Delete Group",
                                                     onclick: move |e| {
                                                         e.stop_propagation();
-                                                        formatter_items.write().remove(item_idx);
+                                                        edit_items(formatter_items, history, "Delete group", |items| {
+                                                            items.remove(item_idx);
+                                                        });
                                                         selected_group_idx.set(None);
                                                     },
                                                     "×"
                                                 }
                                             }
-                                            for (entry_idx , entry) in entries.iter().enumerate() {
+                                            for (entry_idx , entry) in entries.iter().enumerate().filter(|_| !is_collapsed) {
                                                 {
-                                                    let duration_clone = entry.duration.clone();
-                                                    let end_time_clone = entry.end_time.clone();
+                                                    let instance_id = entry.instance_id.clone();
+                                                    let duration_clone = entry.duration;
+                                                    let end_time_clone = entry.end_time;
                                                     let count_to_end = entry.count_to_end;
+                                                    let locked = entry.locked;
+                                                    let fixed = entry.fixed;
+                                                    let history_tip = timing_history.read().lookup(entry.presentation_uuid.as_deref(), &entry.name).map(|r| r.summary()).unwrap_or_default();
+                                                    let is_selected = selection.read().contains(&EntryPath::in_group(item_idx, entry_idx));
+                                                    let drop_class = if *drag_over.read() == Some(DropTarget::GroupEntry(item_idx, entry_idx)) {
+                                                        " drag-over-top"
+                                                    } else {
+                                                        ""
+                                                    };
                                                     rsx! {
-                                                        div { class: "timeline-entry nested editable-entry",
-                                                            div { class: "entry-main",
-                                                                span { class: "entry-title", "{entry.name}" }
-                                                                button {
-                                                                    class: "btn-remove",
-                                                                    onclick: move |e| {
+                                                        div {
+                                                            class: if is_selected { format!("timeline-entry nested editable-entry draggable selected{}", drop_class) } else { format!("timeline-entry nested editable-entry draggable{}", drop_class) },
+                                                            ondragenter: move |e| {
+                                                                e.prevent_default();
+                                                                e.stop_propagation();
+                                                                if let Some(source) = dragged.read().as_ref() {
+                                                                    drag_over.set(Some(group_target(&formatter_items.read(), source, item_idx, DropTarget::GroupEntry(item_idx, entry_idx))));
+                                                                }
+                                                            },
+                                                            draggable: true,
+                                                            ondragstart: move |e| {
+                                                                // Don't let the group pick this up as a drag of the whole group
+                                                                e.stop_propagation();
+                                                                dragged.set(Some(DragSource::Entry(EntryPath::in_group(item_idx, entry_idx))));
+                                                            },
+                                                            ondragend: move |e| {
+                                                                e.stop_propagation();
+                                                                dragged.set(None);
+                                                                drag_over.set(None);
+                                                            },
+                                                            style: if !entry.color.is_empty() { format!("border-left: 3px solid {};", entry.color) } else { String::new() },
+                                                            div {
+                                                                class: "entry-main cursor-pointer",
+                                                                onclick: move |e| {
+                                                                    e.stop_propagation();
+                                                                    select_entry(
+                                                                        selection,
+                                                                        selection_anchor,
+                                                                        &formatter_items.read(),
+                                                                        EntryPath::in_group(item_idx, entry_idx),
+                                                                        e.modifiers(),
+                                                                    );
+                                                                },
+                                                                if entry.manual {
+                                                                    input {
+                                                                        class: "entry-title bg-transparent border border-transparent px-1 rounded min-w-0 hover:border-zinc-700 focus:outline-none focus:border-cyan-500",
+                                                                        value: "{entry.name}",
+                                                                        onkeydown: keep_text_undo,
+                                                                        onclick: move |e| e.stop_propagation(),
+                                                                        oninput: move |e| {
+                                                                            edit_items_coalesced(formatter_items, history, "Rename entry", instance_id.clone(), |items| {
+                                                                                if let Some(entry) = EntryPath::in_group(item_idx, entry_idx).entry_mut(items) {
+                                                                                    entry.name = e.value();
+                                                                                }
+                                                                            });
+                                                                        },
+                                                                    }
+                                                                    span {
+                                                                        class: "text-[0.6rem] font-bold text-emerald-500 bg-emerald-500/10 border border-emerald-500/20 px-1.5 rounded shrink-0 mr-1",
+                                                                        title: "Manual entry - not from ProPresenter",
+                                                                        "MANUAL"
+                                                                    }
+                                                                } else {
+                                                                    span { class: "entry-title", "{entry.name}" }
+                                                                }
+                                                                if let Some(overrun) = conflicts.get(&entry.instance_id) {
+                                                                    span {
+                                                                        class: "text-[0.6rem] font-bold text-red-500 bg-red-500/10 border border-red-500/30 px-1.5 rounded shrink-0 mr-1",
+                                                                        title: "Locked end time can't be met: the entry before runs {overrun} into this one",
+                                                                        "⚠ +{overrun}"
+                                                                    }
+                                                                }
+                                                                if let Some(&uses) = use_counts.get(&entry.item_id).filter(|&&n| n > 1) {
+                                                                    span {
+                                                                        class: "text-[0.6rem] font-bold text-amber-500 bg-amber-500/10 border border-amber-500/20 px-1.5 rounded shrink-0 mr-1",
+                                                                        title: "This ProPresenter item is planned {uses} times",
+                                                                        "⚠ ×{uses}"
+                                                                    }
+                                                                }
+                                                                if entry_idx > 0 {
+                                                                    button {
+                                                                        class: "btn-split",
+                                                                        title: "Split the group here",
+                                                                        onclick: move |e| {
+                                                                            e.stop_propagation();
+                                                                            edit_items(formatter_items, history, "Split group", |items| split_group(items, item_idx, entry_idx));
+                                                                        },
+                                                                        "✂"
+                                                                    }
+                                                                }
+                                                                button {
+                                                                    class: "btn-remove",
+                                                                    onclick: move |e| {
                                                                         e.stop_propagation();
-                                                                        if let FormatterItem::Group { entries, .. } = &mut formatter_items
-                                                                            .write()[item_idx]
-                                                                        {
-                                                                            entries.remove(entry_idx);
-                                                                        }
+                                                                        edit_items(formatter_items, history, "Remove entry", |items| {
+                                                                            if let FormatterItem::Group { entries, .. } = &mut items[item_idx] {
+                                                                                entries.remove(entry_idx);
+                                                                            }
+                                                                        });
                                                                     },
                                                                     "×"
                                                                 }
@@ -736,7 +1348,9 @@ pub fn Formatter() -> Element {
                                                                         r#type: "text",
                                                                         class: "time-input cursor-pointer",
                                                                         value: "{duration_clone}",
+                                                                        title: "{history_tip}",
                                                                         readonly: true,
+                                                                        onkeydown: keep_text_undo,
                                                                         onclick: move |e| {
                                                                             e.stop_propagation();
                                                                             active_time_edit
@@ -745,7 +1359,7 @@ pub fn Formatter() -> Element {
                                                                                         item_idx,
                                                                                         sub_item_idx: Some(entry_idx),
                                                                                         field: TimeField::Duration,
-                                                                                        current_value: duration_clone.clone(),
+                                                                                        current_value: duration_clone.as_ms(),
                                                                                     }),
                                                                                 );
                                                                         },
@@ -758,6 +1372,7 @@ pub fn Formatter() -> Element {
                                                                         class: "time-input cursor-pointer",
                                                                         value: "{end_time_clone}",
                                                                         readonly: true,
+                                                                        onkeydown: keep_text_undo,
                                                                         onclick: move |e| {
                                                                             e.stop_propagation();
                                                                             active_time_edit
@@ -766,7 +1381,7 @@ pub fn Formatter() -> Element {
                                                                                         item_idx,
                                                                                         sub_item_idx: Some(entry_idx),
                                                                                         field: TimeField::EndTime,
-                                                                                        current_value: end_time_clone.clone(),
+                                                                                        current_value: end_time_clone.schedule_ms(),
                                                                                     }),
                                                                                 );
                                                                         },
@@ -779,15 +1394,47 @@ pub fn Formatter() -> Element {
                                                                         checked: count_to_end,
                                                                         onclick: move |e| e.stop_propagation(),
                                                                         onchange: move |e| {
-                                                                            if let FormatterItem::Group { entries, .. } = &mut formatter_items
-                                                                                .write()[item_idx]
-                                                                            {
-                                                                                entries[entry_idx].count_to_end = e.checked();
-                                                                            }
+                                                                            edit_items(formatter_items, history, "Toggle count to end", |items| {
+                                                                                if let FormatterItem::Group { entries, .. } = &mut items[item_idx] {
+                                                                                    entries[entry_idx].count_to_end = e.checked();
+                                                                                }
+                                                                            });
                                                                         },
                                                                     }
                                                                     label { r#for: "cte-{item_idx}-{entry_idx}", "CTE" }
                                                                 }
+                                                                div { class: "field-group checkbox-group",
+                                                                    input {
+                                                                        r#type: "checkbox",
+                                                                        id: "lock-{item_idx}-{entry_idx}",
+                                                                        checked: locked,
+                                                                        onclick: move |e| e.stop_propagation(),
+                                                                        onchange: move |e| {
+                                                                            edit_items(formatter_items, history, "Toggle locked end", |items| {
+                                                                                if let FormatterItem::Group { entries, .. } = &mut items[item_idx] {
+                                                                                    entries[entry_idx].locked = e.checked();
+                                                                                }
+                                                                            });
+                                                                        },
+                                                                    }
+                                                                    label { r#for: "lock-{item_idx}-{entry_idx}", title: "Locked end time - ripple edits stop here", "Lock" }
+                                                                }
+                                                                div { class: "field-group checkbox-group",
+                                                                    input {
+                                                                        r#type: "checkbox",
+                                                                        id: "fixed-{item_idx}-{entry_idx}",
+                                                                        checked: fixed,
+                                                                        onclick: move |e| e.stop_propagation(),
+                                                                        onchange: move |e| {
+                                                                            edit_items(formatter_items, history, "Toggle fixed duration", |items| {
+                                                                                if let FormatterItem::Group { entries, .. } = &mut items[item_idx] {
+                                                                                    entries[entry_idx].fixed = e.checked();
+                                                                                }
+                                                                            });
+                                                                        },
+                                                                    }
+                                                                    label { r#for: "fixed-{item_idx}-{entry_idx}", title: "Fixed duration - fit-to-time leaves it alone", "Fixed" }
+                                                                }
                                                             }
                                                         }
                                                     }
@@ -796,20 +1443,14 @@ pub fn Formatter() -> Element {
                                         }
                                     }
                                 }
-                                FormatterItem::Reference { title, item_type: _, mode, .. } => {
+                                FormatterItem::Reference { title, item_type, mode, .. } => {
+                                    let mode = *mode;
+                                    let valid_modes = InsertionMode::valid_for(item_type);
                                     // Calculate drag over class
-                                    let drag_over_class = if let (Some(dragged_idx), Some(over_idx)) = (*dragged_item_idx.read(), *drag_over_idx.read()) {
-                                        if over_idx == current_idx && dragged_idx != current_idx {
-                                            if dragged_idx < current_idx {
-                                                " border-b-2 border-b-cyan-500"
-                                            } else {
-                                                " border-t-2 border-t-cyan-500"
-                                            }
-                                        } else {
-                                            ""
-                                        }
-                                    } else {
-                                        ""
+                                    let drag_over_class = match drop_indicator(&dragged.read(), &drag_over.read(), current_idx) {
+                                        Some(true) => " border-b-2 border-b-cyan-500",
+                                        Some(false) => " border-t-2 border-t-cyan-500",
+                                        None => "",
                                     };
 
                                     rsx! {
@@ -817,12 +1458,16 @@ pub fn Formatter() -> Element {
                                             class: format!("pl-4 relative before:content-[''] before:absolute before:left-0 before:top-0 before:bottom-0 before:w-1 before:bg-zinc-800 flex flex-col items-stretch p-3 rounded-md bg-zinc-950/50 border border-zinc-800/50 mb-1 backdrop-blur-sm cursor-grab active:cursor-grabbing{}", drag_over_class),
                                             draggable: true,
                                             ondragstart: move |_| {
-                                                dragged_item_idx.set(Some(current_idx));
+                                                dragged.set(Some(DragSource::Item(current_idx)));
+                                            },
+                                            ondragend: move |_| {
+                                                dragged.set(None);
+                                                drag_over.set(None);
                                             },
                                             ondragenter: move |e| {
                                                 e.prevent_default();
-                                                if dragged_item_idx.read().is_some() {
-                                                    drag_over_idx.set(Some(current_idx));
+                                                if let Some(source) = dragged.read().as_ref() {
+                                                    drag_over.set(Some(top_level_target(source, current_idx)));
                                                 }
                                             },
                                             ondragover: move |e| {
@@ -830,32 +1475,25 @@ pub fn Formatter() -> Element {
                                             },
                                             ondrop: move |e| {
                                                 e.prevent_default();
-                                                let source_idx_opt = *dragged_item_idx.read();
-                                                if let Some(source_idx) = source_idx_opt {
-                                                    if source_idx != current_idx {
-                                                        let mut items = formatter_items.write();
-                                                        let item = items.remove(source_idx);
-                                                        items.insert(current_idx, item);
-                                                    }
-                                                }
-                                                dragged_item_idx.set(None);
-                                                drag_over_idx.set(None);
+                                                finish_drop();
                                             },
                                             div { class: "flex items-center text-sm",
-                                                div { class: "text-cyan-500 font-bold mr-2 w-4 text-center",
-                                                    if matches!(mode, InsertionMode::Into) {
-                                                        "↳"
-                                                    } else {
-                                                        "↓"
-                                                    }
-                                                }
+                                                div { class: "text-cyan-500 font-bold mr-2 w-4 text-center", "{mode.symbol()}" }
                                                 div { class: "flex-1 flex items-center gap-2 overflow-hidden",
                                                     span { class: "text-zinc-600 cursor-grab select-none font-bold", "⋮⋮" }
-                                                    span { class: "text-[0.6rem] font-bold px-1.5 py-0.5 rounded bg-zinc-800 text-zinc-400",
-                                                        if matches!(mode, InsertionMode::Into) {
-                                                            "INSERT INTO"
-                                                        } else {
-                                                            "INSERT AFTER"
+                                                    select {
+                                                        class: "text-[0.6rem] font-bold px-1 py-0.5 rounded bg-zinc-800 text-zinc-400 border-none focus:outline-none cursor-pointer",
+                                                        value: "{mode.label()}",
+                                                        onchange: move |e| {
+                                                            let Some(new_mode) = valid_modes.iter().copied().find(|m| m.label() == e.value()) else { return };
+                                                            edit_items(formatter_items, history, "Change insertion mode", |items| {
+                                                                if let Some(FormatterItem::Reference { mode, .. }) = items.get_mut(item_idx) {
+                                                                    *mode = new_mode;
+                                                                }
+                                                            });
+                                                        },
+                                                        for option_mode in valid_modes.iter().copied() {
+                                                            option { value: "{option_mode.label()}", selected: option_mode == mode, "{option_mode.label()}" }
                                                         }
                                                     }
                                                     span { class: "flex-1 truncate text-zinc-300 italic", "{title}" }
@@ -863,7 +1501,9 @@ pub fn Formatter() -> Element {
                                                 button {
                                                     class: "w-7 h-7 flex items-center justify-center rounded text-zinc-500 hover:bg-red-500/10 hover:border-red-500/30 hover:text-red-500 transition-all font-mono text-lg ml-2",
                                                     onclick: move |_| {
-                                                        formatter_items.write().remove(item_idx);
+                                                        edit_items(formatter_items, history, "Remove item", |items| {
+                                                            items.remove(item_idx);
+                                                        });
                                                     },
                                                     "×"
                                                 }
@@ -871,9 +1511,170 @@ pub fn Formatter() -> Element {
                                         }
                                     }
                                 }
+                                FormatterItem::Delay { id, duration } => {
+                                    let delay_key = id.clone();
+                                    let duration = *duration;
+                                    let drag_over_class = match drop_indicator(&dragged.read(), &drag_over.read(), current_idx) {
+                                        Some(true) => " border-b-2 border-b-cyan-500",
+                                        Some(false) => " border-t-2 border-t-cyan-500",
+                                        None => "",
+                                    };
+
+                                    rsx! {
+                                        div {
+                                            class: format!("flex items-center gap-2 p-3 rounded-md bg-amber-500/5 border border-amber-500/30 mb-1 text-sm cursor-grab active:cursor-grabbing{}", drag_over_class),
+                                            draggable: true,
+                                            ondragstart: move |_| {
+                                                dragged.set(Some(DragSource::Item(current_idx)));
+                                            },
+                                            ondragend: move |_| {
+                                                dragged.set(None);
+                                                drag_over.set(None);
+                                            },
+                                            ondragenter: move |e| {
+                                                e.prevent_default();
+                                                if let Some(source) = dragged.read().as_ref() {
+                                                    drag_over.set(Some(top_level_target(source, current_idx)));
+                                                }
+                                            },
+                                            ondragover: move |e| {
+                                                e.prevent_default();
+                                            },
+                                            ondrop: move |e| {
+                                                e.prevent_default();
+                                                finish_drop();
+                                            },
+                                            span { class: "text-zinc-600 cursor-grab select-none font-bold", "⋮⋮" }
+                                            span { class: "text-[0.6rem] font-bold px-1.5 py-0.5 rounded bg-amber-500/20 text-amber-500", "DELAY" }
+                                            div { class: "w-24",
+                                                DurationInput {
+                                                    value: Some(duration),
+                                                    on_change: move |d: Option<Duration>| {
+                                                        let Some(d) = d else { return };
+                                                        edit_items_coalesced(formatter_items, history, "Set delay", delay_key.clone(), |items| {
+                                                            if let Some(FormatterItem::Delay { duration, .. }) = items.get_mut(item_idx) {
+                                                                *duration = d;
+                                                            }
+                                                        });
+                                                    },
+                                                }
+                                            }
+                                            span { class: "flex-1 truncate text-xs text-zinc-500 italic", "Pushes everything after it back" }
+                                            button {
+                                                class: "w-7 h-7 flex items-center justify-center rounded text-zinc-500 hover:bg-red-500/10 hover:border-red-500/30 hover:text-red-500 transition-all font-mono text-lg ml-2",
+                                                onclick: move |_| {
+                                                    edit_items(formatter_items, history, "Remove item", |items| {
+                                                        items.remove(item_idx);
+                                                    });
+                                                },
+                                                "×"
+                                            }
+                                        }
+                                    }
+                                }
+                                FormatterItem::Milestone { id, title, cue, color, .. } => {
+                                    // Each handler below owns a copy of the id for its undo coalescing key
+                                    let (cue_key, title_key, color_key) = (id.clone(), id.clone(), id.clone());
+                                    let drag_over_class = match drop_indicator(&dragged.read(), &drag_over.read(), current_idx) {
+                                        Some(true) => " border-b-2 border-b-cyan-500",
+                                        Some(false) => " border-t-2 border-t-cyan-500",
+                                        None => "",
+                                    };
+
+                                    rsx! {
+                                        div {
+                                            class: format!("flex items-center gap-2 p-3 rounded-md bg-zinc-950/50 border border-dashed border-zinc-700 mb-1 text-sm cursor-grab active:cursor-grabbing{}", drag_over_class),
+                                            style: if !color.is_empty() { format!("border-left: 3px solid {};", color) } else { String::new() },
+                                            draggable: true,
+                                            ondragstart: move |_| {
+                                                dragged.set(Some(DragSource::Item(current_idx)));
+                                            },
+                                            ondragend: move |_| {
+                                                dragged.set(None);
+                                                drag_over.set(None);
+                                            },
+                                            ondragenter: move |e| {
+                                                e.prevent_default();
+                                                if let Some(source) = dragged.read().as_ref() {
+                                                    drag_over.set(Some(top_level_target(source, current_idx)));
+                                                }
+                                            },
+                                            ondragover: move |e| {
+                                                e.prevent_default();
+                                            },
+                                            ondrop: move |e| {
+                                                e.prevent_default();
+                                                finish_drop();
+                                            },
+                                            span { class: "text-zinc-600 cursor-grab select-none font-bold", "⋮⋮" }
+                                            span { class: "text-[0.6rem] font-bold px-1.5 py-0.5 rounded bg-zinc-800 text-zinc-300", "⚑ MILESTONE" }
+                                            input {
+                                                class: "bg-transparent border border-transparent px-1 py-0.5 rounded text-zinc-200 hover:border-zinc-700 focus:outline-none focus:border-cyan-500 w-16 font-mono text-xs",
+                                                placeholder: "cue",
+                                                value: "{cue}",
+                                                onkeydown: keep_text_undo,
+                                                oninput: move |e| {
+                                                    edit_items_coalesced(formatter_items, history, "Edit milestone", cue_key.clone(), |items| {
+                                                        if let Some(FormatterItem::Milestone { cue, .. }) = items.get_mut(item_idx) {
+                                                            *cue = e.value();
+                                                        }
+                                                    });
+                                                },
+                                            }
+                                            input {
+                                                class: "bg-transparent border border-transparent px-1 py-0.5 rounded text-zinc-200 hover:border-zinc-700 focus:outline-none focus:border-cyan-500 flex-1 min-w-0",
+                                                value: "{title}",
+                                                onkeydown: keep_text_undo,
+                                                oninput: move |e| {
+                                                    edit_items_coalesced(formatter_items, history, "Edit milestone", title_key.clone(), |items| {
+                                                        if let Some(FormatterItem::Milestone { title, .. }) = items.get_mut(item_idx) {
+                                                            *title = e.value();
+                                                        }
+                                                    });
+                                                },
+                                            }
+                                            input {
+                                                r#type: "color",
+                                                class: "w-7 h-6 bg-transparent cursor-pointer",
+                                                value: if color.is_empty() { "#000000".to_string() } else { color.clone() },
+                                                oninput: move |e| {
+                                                    edit_items_coalesced(formatter_items, history, "Edit milestone", color_key.clone(), |items| {
+                                                        if let Some(FormatterItem::Milestone { color, .. }) = items.get_mut(item_idx) {
+                                                            *color = e.value();
+                                                        }
+                                                    });
+                                                },
+                                            }
+                                            button {
+                                                class: "w-7 h-7 flex items-center justify-center rounded text-zinc-500 hover:bg-red-500/10 hover:border-red-500/30 hover:text-red-500 transition-all font-mono text-lg ml-2",
+                                                onclick: move |_| {
+                                                    edit_items(formatter_items, history, "Remove item", |items| {
+                                                        items.remove(item_idx);
+                                                    });
+                                                },
+                                                "×"
+                                            }
+                                        }
+                                    }
+                                }
                             }
                             }
                         }
+                        if dragged.read().is_some() {
+                            div {
+                                class: if *drag_over.read() == Some(DropTarget::Gap(item_count)) { "drop-gap drop-end active" } else { "drop-gap drop-end" },
+                                ondragenter: move |e| {
+                                    e.prevent_default();
+                                    drag_over.set(Some(DropTarget::Gap(item_count)));
+                                },
+                                ondragover: move |e| e.prevent_default(),
+                                ondrop: move |e| {
+                                    e.prevent_default();
+                                    finish_drop();
+                                },
+                                "DROP AT END"
+                            }
+                        }
                     }
                     // Push to OnTime button
                     div { class: "p-4 border-t border-zinc-800/50",
@@ -889,6 +1690,7 @@ pub fn Formatter() -> Element {
                                     );
                                 let items_data = formatter_items.read().clone();
                                 let ontime_events = ontime_resource.read().clone();
+                                let rundown = ontime_timeline_resource.read().clone().and_then(|r| r.ok());
                                 spawn(async move {
                                     let settings = AppSettings::load();
                                     let base_url = format!("http://{}:{}", settings.ot_host, settings.ot_port);
@@ -935,57 +1737,96 @@ pub fn Formatter() -> Element {
                                                 endpoint,
                                             ),
                                         );
-                                    let (existing_ids, existing_titles): (Vec<String>, Vec<String>) = match ontime_events {
-                                        Some(Ok(events)) => {
-                                            (
-                                                events.iter().map(|e| e.id.clone()).collect(),
-                                                events.iter().map(|e| e.title.clone()).collect(),
-                                            )
-                                        }
-                                        _ => (vec![], vec![]),
+                                    let existing_ids: Vec<String> = match ontime_events {
+                                        Some(Ok(events)) => events.iter().map(|e| e.id.clone()).collect(),
+                                        _ => vec![],
                                     };
-                                    let mut current_after_id = existing_ids.last().cloned();
-                                    let mut current_parent_id: Option<String> = None;
+                                    // Instance id -> Ontime id of everything created by this push
+                                    let mut pushed = std::collections::HashMap::new();
+                                    let mut point = InsertionPoint::after(existing_ids.last().cloned());
+                                    let supports_group_target = rundown.as_ref().is_none_or(|r| r.supports_group_target());
                                     for item in items_data {
                                         match item {
-                                            FormatterItem::Reference { id, item_type: _, mode, .. } => {
-                                                match mode {
-                                                    InsertionMode::After => {
-                                                        current_after_id = Some(id.clone());
-                                                        current_parent_id = None;
+                                            FormatterItem::Reference { id, cue, title, mode, .. } => {
+                                                let planned = match rundown.as_ref().and_then(|r| Some((r, r.resolve_reference(&id, &cue)?))) {
+                                                    Some((rundown, target)) => rundown.insertion_point(target, mode),
+                                                    None => Err(format!("\"{}\" is not in the current rundown", title)),
+                                                };
+                                                match planned {
+                                                    Ok(next) => {
+                                                        point = next;
+                                                        logs.write()
+                                                            .push(
+                                                                format!(
+                                                                    "[{}] Set Context: Mode {:?} ID {}",
+                                                                    chrono::Local::now().format("%H:%M:%S"),
+                                                                    mode,
+                                                                    id,
+                                                                ),
+                                                            );
                                                     }
-                                                    InsertionMode::Into => {
-                                                        current_parent_id = Some(id.clone());
-                                                        current_after_id = None;
+                                                    Err(e) => {
+                                                        logs.write()
+                                                            .push(
+                                                                format!(
+                                                                    "[{}] ✗ Reference skipped: {}",
+                                                                    chrono::Local::now().format("%H:%M:%S"),
+                                                                    e,
+                                                                ),
+                                                            );
                                                     }
                                                 }
+                                            }
+                                            FormatterItem::Delay { duration, .. } => {
+                                                let mut payload = serde_json::json!({ "type": "delay", "duration": duration.as_ms() });
+                                                point.apply(&mut payload);
                                                 logs.write()
                                                     .push(
                                                         format!(
-                                                            "[{}] Set Context: Mode {:?} ID {}",
+                                                            "[{}] Delay: {}",
                                                             chrono::Local::now().format("%H:%M:%S"),
-                                                            mode,
-                                                            id,
+                                                            duration,
                                                         ),
                                                     );
-                                            }
-                                            FormatterItem::Standalone(entry) => {
-                                                if existing_titles.contains(&entry.name) {
-                                                    continue;
+                                                if let Some(id) = post_entry(&client, &endpoint, &payload, logs).await {
+                                                    point.advance(id);
                                                 }
-                                                let duration_ms = parse_duration_to_ms(&entry.duration);
-                                                let end_time_ms = parse_duration_to_ms(&entry.end_time);
-                                                let mut event_payload = serde_json::json!(
-                                                    { "type" : "event", "title" : entry.name, "duration" :
-                                                    duration_ms, "timeEnd" : end_time_ms, "countToEnd" : entry
-                                                    .count_to_end, "linkStart" : entry.link_start }
+                                            }
+                                            FormatterItem::Milestone { title, cue, note, color, .. } => {
+                                                let mut payload = serde_json::json!(
+                                                    { "type": "milestone", "title": title, "cue": cue, "note": note, "colour": color }
                                                 );
-                                                if let Some(ref pid) = current_parent_id {
-                                                    event_payload["parent"] = serde_json::json!(pid);
+                                                point.apply(&mut payload);
+                                                logs.write()
+                                                    .push(
+                                                        format!(
+                                                            "[{}] Milestone: {}",
+                                                            chrono::Local::now().format("%H:%M:%S"),
+                                                            title,
+                                                        ),
+                                                    );
+                                                if let Some(id) = post_entry(&client, &endpoint, &payload, logs).await {
+                                                    point.advance(id);
                                                 }
-                                                if let Some(ref a) = current_after_id {
-                                                    event_payload["after"] = serde_json::json!(a);
+                                            }
+                                            FormatterItem::Standalone(entry) => {
+                                                if already_pushed(&existing_ids, &entry) {
+                                                    logs.write()
+                                                        .push(
+                                                            format!(
+                                                                "[{}] Already in Ontime: {}",
+                                                                chrono::Local::now().format("%H:%M:%S"),
+                                                                entry.name,
+                                                            ),
+                                                        );
+                                                    // What follows still goes after it
+                                                    if let Some(id) = entry.ontime_id {
+                                                        point.advance(id);
+                                                    }
+                                                    continue;
                                                 }
+                                                let mut event_payload = event_payload(&entry);
+                                                point.apply(&mut event_payload);
                                                 logs.write()
                                                     .push(
                                                         format!(
@@ -994,64 +1835,31 @@ pub fn Formatter() -> Element {
                                                             entry.name,
                                                         ),
                                                     );
-                                                match client
-                                                    .post(&endpoint)
-                                                    .header("Content-Type", "application/json")
-                                                    .json(&event_payload)
-                                                    .send()
-                                                    .await
-                                                {
-                                                    Ok(resp) => {
-                                                        let status = resp.status();
-                                                        if status.is_success() {
-                                                            if let Ok(json) = resp.json::<serde_json::Value>().await {
-                                                                if let Some(id) = json.get("id").and_then(|v| v.as_str()) {
-                                                                    current_after_id = Some(id.to_string());
-                                                                    logs.write()
-                                                                        .push(
-                                                                            format!(
-                                                                                "[{}] ✓ Created: {}",
-                                                                                chrono::Local::now().format("%H:%M:%S"),
-                                                                                id,
-                                                                            ),
-                                                                        );
-                                                                }
-                                                            }
-                                                        } else {
-                                                            let body = resp.text().await.unwrap_or_default();
-                                                            logs.write()
-                                                                .push(
-                                                                    format!(
-                                                                        "[{}] ✗ Error {}: {}",
-                                                                        chrono::Local::now().format("%H:%M:%S"),
-                                                                        status,
-                                                                        body,
-                                                                    ),
-                                                                );
-                                                        }
-                                                    }
-                                                    Err(e) => {
+                                                if let Some(id) = post_entry(&client, &endpoint, &event_payload, logs).await {
+                                                    pushed.insert(entry.instance_id.clone(), id.clone());
+                                                    point.advance(id);
+                                                }
+                                            }
+                                            FormatterItem::Group { name, color, entries, target_duration, .. } => {
+                                                let mut group_payload = serde_json::json!(
+                                                    { "type" : "group", "title" : name, "colour" : color }
+                                                );
+                                                point = point.for_group();
+                                                point.apply(&mut group_payload);
+                                                if let Some(target) = target_duration {
+                                                    if supports_group_target {
+                                                        group_payload["targetDuration"] = serde_json::json!(target.as_ms());
+                                                    } else {
                                                         logs.write()
                                                             .push(
                                                                 format!(
-                                                                    "[{}] ✗ Request failed: {}",
+                                                                    "[{}] ⚠ Ontime doesn't support group budgets - {} pushed without one",
                                                                     chrono::Local::now().format("%H:%M:%S"),
-                                                                    e,
+                                                                    name,
                                                                 ),
                                                             );
                                                     }
                                                 }
-                                            }
-                                            FormatterItem::Group { name, color, entries, .. } => {
-                                                let mut group_payload = serde_json::json!(
-                                                    { "type" : "group", "title" : name, "colour" : color }
-                                                );
-                                                if let Some(ref pid) = current_parent_id {
-                                                    group_payload["parent"] = serde_json::json!(pid);
-                                                }
-                                                if let Some(ref a) = current_after_id {
-                                                    group_payload["after"] = serde_json::json!(a);
-                                                }
                                                 logs.write()
                                                     .push(
                                                         format!(
@@ -1060,86 +1868,37 @@ pub fn Formatter() -> Element {
                                                             name,
                                                         ),
                                                     );
-                                                let group_id = match client
-                                                    .post(&endpoint)
-                                                    .header("Content-Type", "application/json")
-                                                    .json(&group_payload)
-                                                    .send()
-                                                    .await
-                                                {
-                                                    Ok(resp) => {
-                                                        let status = resp.status();
-                                                        if status.is_success() {
-                                                            let result = resp
-                                                                .json::<serde_json::Value>()
-                                                                .await
-                                                                .ok()
-                                                                .and_then(|j| {
-                                                                    j.get("id").and_then(|v| v.as_str()).map(|s| s.to_string())
-                                                                });
-                                                            if let Some(ref id) = result {
-                                                                logs.write()
-                                                                    .push(
-                                                                        format!(
-                                                                            "[{}] ✓ Group created: {}",
-                                                                            chrono::Local::now().format("%H:%M:%S"),
-                                                                            id,
-                                                                        ),
-                                                                    );
-                                                            }
-                                                            result
-                                                        } else {
-                                                            let body = resp.text().await.unwrap_or_default();
-                                                            logs.write()
-                                                                .push(
-                                                                    format!(
-                                                                        "[{}] ✗ Group error {}: {}",
-                                                                        chrono::Local::now().format("%H:%M:%S"),
-                                                                        status,
-                                                                        body,
-                                                                    ),
-                                                                );
-                                                            None
-                                                        }
-                                                    }
-                                                    Err(e) => {
-                                                        logs.write()
-                                                            .push(
-                                                                format!(
-                                                                    "[{}] ✗ Group request failed: {}",
-                                                                    chrono::Local::now().format("%H:%M:%S"),
-                                                                    e,
-                                                                ),
-                                                            );
-                                                        None
-                                                    }
+                                                let Some(gid) = post_entry(&client, &endpoint, &group_payload, logs).await else {
+                                                    // Posting the entries without their group would scatter them at the top level
+                                                    logs.write()
+                                                        .push(
+                                                            format!(
+                                                                "[{}] ✗ Skipped {} entries of {}: the group wasn't created",
+                                                                chrono::Local::now().format("%H:%M:%S"),
+                                                                entries.len(),
+                                                                name,
+                                                            ),
+                                                        );
+                                                    continue;
                                                 };
-                                                if let Some(ref gid) = group_id {
-                                                    current_after_id = Some(gid.clone());
-                                                }
+                                                point = InsertionPoint::after(Some(gid.clone()));
                                                 let mut internal_after_id = None;
                                                 for entry in entries {
-                                                    if existing_titles.contains(&entry.name) {
+                                                    if already_pushed(&existing_ids, &entry) {
                                                         logs.write()
                                                             .push(
                                                                 format!(
-                                                                    "[{}] Skipping duplicate: {}",
+                                                                    "[{}] Already in Ontime: {}",
                                                                     chrono::Local::now().format("%H:%M:%S"),
                                                                     entry.name,
                                                                 ),
                                                             );
+                                                        // The next entry still goes after it
+                                                        internal_after_id = entry.ontime_id;
                                                         continue;
                                                     }
-                                                    let duration_ms = parse_duration_to_ms(&entry.duration);
-                                                    let end_time_ms = parse_duration_to_ms(&entry.end_time);
-                                                    let mut event_payload = serde_json::json!(
-                                                        { "type" : "event", "title" : entry.name, "duration" :
-                                                        duration_ms, "timeEnd" : end_time_ms, "countToEnd" : entry
-                                                        .count_to_end, "linkStart" : entry.link_start }
-                                                    );
-                                                    if let Some(ref gid) = group_id {
-                                                        event_payload["parent"] = serde_json::json!(gid);
-                                                    }
+                                                    let mut event_payload = event_payload(&entry);
+                                                    event_payload["parent"] = serde_json::json!(gid);
                                                     if let Some(ref a) = internal_after_id {
                                                         event_payload["after"] = serde_json::json!(a);
                                                     }
@@ -1151,52 +1910,9 @@ pub fn Formatter() -> Element {
                                                                 entry.name,
                                                             ),
                                                         );
-                                                    match client
-                                                        .post(&endpoint)
-                                                        .header("Content-Type", "application/json")
-                                                        .json(&event_payload)
-                                                        .send()
-                                                        .await
-                                                    {
-                                                        Ok(resp) => {
-                                                            let status = resp.status();
-                                                            if status.is_success() {
-                                                                if let Ok(json) = resp.json::<serde_json::Value>().await {
-                                                                    if let Some(id) = json.get("id").and_then(|v| v.as_str()) {
-                                                                        internal_after_id = Some(id.to_string());
-                                                                        logs.write()
-                                                                            .push(
-                                                                                format!(
-                                                                                    "[{}] ✓ Created: {}",
-                                                                                    chrono::Local::now().format("%H:%M:%S"),
-                                                                                    id,
-                                                                                ),
-                                                                            );
-                                                                    }
-                                                                }
-                                                            } else {
-                                                                let body = resp.text().await.unwrap_or_default();
-                                                                logs.write()
-                                                                    .push(
-                                                                        format!(
-                                                                            "[{}] ✗ Error {}: {}",
-                                                                            chrono::Local::now().format("%H:%M:%S"),
-                                                                            status,
-                                                                            body,
-                                                                        ),
-                                                                    );
-                                                            }
-                                                        }
-                                                        Err(e) => {
-                                                            logs.write()
-                                                                .push(
-                                                                    format!(
-                                                                        "[{}] ✗ Request failed: {}",
-                                                                        chrono::Local::now().format("%H:%M:%S"),
-                                                                        e,
-                                                                    ),
-                                                                );
-                                                        }
+                                                    if let Some(id) = post_entry(&client, &endpoint, &event_payload, logs).await {
+                                                        pushed.insert(entry.instance_id.clone(), id.clone());
+                                                        internal_after_id = Some(id);
                                                     }
                                                 }
                                             }
@@ -1210,7 +1926,9 @@ pub fn Formatter() -> Element {
                                                 chrono::Local::now().format("%H:%M:%S"),
                                             ),
                                         );
-                                    formatter_items.write().clear();
+                                    // Written outside history so undoing the clear brings the ids back too
+                                    record_pushed_ids(&mut formatter_items.write(), &pushed);
+                                    edit_items(formatter_items, history, "Push to Ontime", |items| items.clear());
                                     selected_group_idx.set(None);
                                     ontime_timeline_resource.restart();
                                     ontime_resource.restart();
@@ -1230,6 +1948,59 @@ pub fn Formatter() -> Element {
                             "↻"
                         }
                     }
+                    // Reference by cue - survives the entry being recreated with a new id
+                    div { class: "px-3 py-2 border-b border-zinc-800/80 flex items-center gap-2",
+                        input {
+                            class: "flex-1 min-w-0 bg-zinc-950 border border-zinc-800 rounded px-2 py-1 text-xs font-mono text-zinc-200 focus:outline-none focus:border-cyan-500/50 placeholder-zinc-700",
+                            placeholder: "Reference by cue",
+                            value: "{ref_cue}",
+                            onkeydown: keep_text_undo,
+                            oninput: move |e| ref_cue.set(e.value()),
+                        }
+                        button {
+                            class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 text-[0.65rem] font-bold hover:text-cyan-500 hover:border-cyan-500/50 transition-all disabled:opacity-40",
+                            disabled: ref_cue().trim().is_empty(),
+                            onclick: move |_| {
+                                let cue = ref_cue().trim().to_string();
+                                let found = match &*ontime_timeline_resource.read() {
+                                    Some(Ok(rundown)) => rundown.resolve_reference("", &cue).cloned(),
+                                    _ => None,
+                                };
+                                let Some(entry) = found else {
+                                    logs.write()
+                                        .push(
+                                            format!(
+                                                "[{}] ✗ No rundown entry with cue {}",
+                                                chrono::Local::now().format("%H:%M:%S"),
+                                                cue,
+                                            ),
+                                        );
+                                    return;
+                                };
+                                let mode = if entry.entry_type == "group" { InsertionMode::IntoEnd } else { InsertionMode::After };
+                                logs.write()
+                                    .push(
+                                        format!(
+                                            "[{}] Added Reference: {} (cue {})",
+                                            chrono::Local::now().format("%H:%M:%S"),
+                                            entry.title,
+                                            cue,
+                                        ),
+                                    );
+                                let reference = FormatterItem::Reference {
+                                    id: entry.id.clone(),
+                                    cue,
+                                    title: entry.title.clone(),
+                                    item_type: entry.entry_type.clone(),
+                                    mode,
+                                    time_end: TimeOfDay::ontime_end(entry.time_start, entry.time_end, entry.day_offset),
+                                };
+                                edit_items(formatter_items, history, format!("Add reference {}", entry.title), |items| items.push(reference));
+                                ref_cue.set(String::new());
+                            },
+                            "+ REF"
+                        }
+                    }
                     div { class: "timeline-content",
                         match &*ontime_timeline_resource.read() {
                             Some(Ok(rundown)) => rsx! {
@@ -1243,8 +2014,9 @@ pub fn Formatter() -> Element {
                                             {
                                                 let entry_id_clone = entry.id.clone();
                                                 let entry_title_clone = entry.title.clone();
+                                                let entry_cue_clone = entry.cue.clone();
                                                 let entry_type = entry.entry_type.clone();
-                                                let entry_time_end = entry.time_end;
+                                                let entry_time_end = TimeOfDay::ontime_end(entry.time_start, entry.time_end, entry.day_offset);
 
         
 
@@ -1286,7 +2058,7 @@ pub fn Formatter() -> Element {
                                                                 return;
                                                             }
                                                             let mode = if entry_type == "group" {
-                                                                InsertionMode::Into
+                                                                InsertionMode::IntoEnd
                                                             } else {
                                                                 InsertionMode::After
                                                             };
@@ -1298,15 +2070,20 @@ pub fn Formatter() -> Element {
                                                                         entry_title_clone,
                                                                     ),
                                                                 );
-                                                            formatter_items
-                                                                .write()
-                                                                .push(FormatterItem::Reference {
-                                                                    id: entry_id_clone.clone(),
-                                                                    title: entry_title_clone.clone(),
-                                                                    item_type: entry_type.clone(),
-                                                                    mode,
-                                                                    time_end: entry_time_end,
-                                                                });
+                                                            let reference = FormatterItem::Reference {
+                                                                id: entry_id_clone.clone(),
+                                                                cue: entry_cue_clone.clone(),
+                                                                title: entry_title_clone.clone(),
+                                                                item_type: entry_type.clone(),
+                                                                mode,
+                                                                time_end: entry_time_end,
+                                                            };
+                                                            edit_items(
+                                                                formatter_items,
+                                                                history,
+                                                                format!("Add reference {}", entry_title_clone),
+                                                                |items| items.push(reference),
+                                                            );
                                                         },
                                                         div { class: "flex items-center gap-2",
                                                             if !entry.cue.is_empty() {
@@ -1316,8 +2093,8 @@ pub fn Formatter() -> Element {
                                                             if entry.duration > 0 {
                                                                 span { class: "text-[0.65rem] font-mono text-zinc-500", "{format_ms_to_duration(entry.duration)}" }
                                                             }
-                                                            if entry.time_end > 0 {
-                                                                span { class: "text-[0.65rem] font-mono text-zinc-600", "→ {format_ms_to_duration(entry.time_end)}" }
+                                                            if !entry_time_end.is_midnight() {
+                                                                span { class: "text-[0.65rem] font-mono text-zinc-600", "→ {entry_time_end}" }
                                                             }
                                                             if is_referenced {
                                                                 div { class: "ml-auto text-emerald-500 font-bold text-[0.6rem] uppercase tracking-wider bg-emerald-500/10 px-1.5 rounded border border-emerald-500/20",
@@ -1340,6 +2117,8 @@ pub fn Formatter() -> Element {
                             },
                         }
                     }
+                    RehearsalPanel { formatter_items, history, timing_history, logs }
+                    LiveRebalance { logs }
                 }
             }
             // Live Logs Panel
@@ -1366,35 +2145,79 @@ pub fn Formatter() -> Element {
                     }
                 }
             }
+            if let Some(paths) = fit_paths() {
+                FitDialog { formatter_items, history, paths, on_close: move |_| fit_paths.set(None) }
+            }
+            if let Some(table) = import_table() {
+                ImportDialog {
+                    formatter_items,
+                    history,
+                    table,
+                    playlist_items: match &*playlist_resource.read() {
+                        Some(Ok(items)) => items.clone(),
+                        _ => vec![],
+                    },
+                    logs,
+                    on_close: move |_| import_table.set(None),
+                }
+            }
             // Time Picker Modal Render
             if let Some(ctx) = active_time_edit.read().clone() {
                 TimePicker {
                     value: ctx.current_value,
                     field: ctx.field,
+                    // Nearest reference above the edited item resolves `ref` expressions
+                    reference: formatter_items
+                        .read()
+                        .iter()
+                        .take(ctx.item_idx)
+                        .rev()
+                        .find_map(|item| match item {
+                            FormatterItem::Reference { time_end, .. } if !time_end.is_midnight() => {
+                                Some(time_end.schedule_ms())
+                            }
+                            _ => None,
+                        }),
                     on_close: move |_| active_time_edit.set(None),
-                    on_save: move |new_val: String| {
-                        let mut items = formatter_items.write();
-                        if let Some(item) = items.get_mut(ctx.item_idx) {
-                            match item {
-                                FormatterItem::Standalone(entry) => {
-                                    match ctx.field {
-                                        TimeField::Duration => entry.duration = new_val,
-                                        TimeField::EndTime => entry.end_time = new_val,
+                    on_save: move |new_val: u64| {
+                        let previous_end = previous_end_time(&formatter_items.read(), ctx.item_idx, ctx.sub_item_idx);
+                        let label = match ctx.field {
+                            TimeField::Duration => "Set duration",
+                            TimeField::EndTime => "Set end time",
+                        };
+                        let path = EntryPath { item_idx: ctx.item_idx, sub_idx: ctx.sub_item_idx };
+                        let ripple_on = ripple();
+                        edit_items(formatter_items, history, label, |items| {
+                            let before = path.entry(items).map(|e| (e.end_time, e.duration));
+                            if let Some(item) = items.get_mut(ctx.item_idx) {
+                                match item {
+                                    FormatterItem::Standalone(entry) => {
+                                        match ctx.field {
+                                            TimeField::Duration => entry.duration = Duration::from_ms(new_val),
+                                            TimeField::EndTime => {
+                                                entry.end_time = roll_end_time(new_val, previous_end)
+                                            }
+                                        }
                                     }
-                                }
-                                FormatterItem::Group { entries, .. } => {
-                                    if let Some(sub_idx) = ctx.sub_item_idx {
-                                        if let Some(entry) = entries.get_mut(sub_idx) {
-                                            match ctx.field {
-                                                TimeField::Duration => entry.duration = new_val,
-                                                TimeField::EndTime => entry.end_time = new_val,
+                                    FormatterItem::Group { entries, .. } => {
+                                        if let Some(sub_idx) = ctx.sub_item_idx {
+                                            if let Some(entry) = entries.get_mut(sub_idx) {
+                                                match ctx.field {
+                                                    TimeField::Duration => entry.duration = Duration::from_ms(new_val),
+                                                    TimeField::EndTime => {
+                                                        entry.end_time = roll_end_time(new_val, previous_end)
+                                                    }
+                                                }
                                             }
                                         }
                                     }
+                                    _ => {}
                                 }
-                                _ => {}
                             }
-                        }
+                            if let (true, Some((old_end, old_duration))) = (ripple_on, before) {
+                                ripple_edit(items, path, old_end, old_duration, ctx.field);
+                            }
+                        });
                         active_time_edit.set(None);
                     },
                 }
@@ -0,0 +1,166 @@
+use dioxus::prelude::*;
+use crate::types::{EditCommand, EditHistory, FormatterItem};
+
+/// Apply a change to the formatter plan and record it as an undoable step
+pub fn edit_items(
+    mut formatter_items: Signal<Vec<FormatterItem>>,
+    mut history: Signal<EditHistory>,
+    label: impl Into<String>,
+    edit: impl FnOnce(&mut Vec<FormatterItem>),
+) {
+    record_edit(&mut formatter_items, &mut history, label.into(), None, edit);
+}
+
+/// Like `edit_items`, but repeated edits with the same label to the same item
+/// (`key`, its id) merge into one step. Used for per-keystroke edits such as
+/// renaming a group.
+pub fn edit_items_coalesced(
+    mut formatter_items: Signal<Vec<FormatterItem>>,
+    mut history: Signal<EditHistory>,
+    label: impl Into<String>,
+    key: impl Into<String>,
+    edit: impl FnOnce(&mut Vec<FormatterItem>),
+) {
+    record_edit(&mut formatter_items, &mut history, label.into(), Some(key.into()), edit);
+}
+
+fn record_edit(
+    formatter_items: &mut Signal<Vec<FormatterItem>>,
+    history: &mut Signal<EditHistory>,
+    label: String,
+    coalesce: Option<String>,
+    edit: impl FnOnce(&mut Vec<FormatterItem>),
+) {
+    let before = formatter_items.peek().clone();
+    let mut after = before.clone();
+    edit(&mut after);
+    if after == before {
+        return;
+    }
+    history.write().record(EditCommand { label, coalesce, before, after: after.clone() });
+    formatter_items.set(after);
+}
+
+/// Step the plan back (`steps` undos) or forward (`steps` redos)
+fn step_history(
+    mut formatter_items: Signal<Vec<FormatterItem>>,
+    mut history: Signal<EditHistory>,
+    mut selected_group_idx: Signal<Option<usize>>,
+    redo: bool,
+    steps: usize,
+) {
+    let mut restored = None;
+    {
+        let mut history = history.write();
+        for _ in 0..steps {
+            let next = if redo { history.redo() } else { history.undo() };
+            match next {
+                Some(items) => restored = Some(items),
+                None => break,
+            }
+        }
+    }
+    if let Some(items) = restored {
+        // Indices may point elsewhere after the plan changes shape
+        selected_group_idx.set(None);
+        formatter_items.set(items);
+    }
+}
+
+/// Handle Ctrl+Z / Ctrl+Shift+Z (Cmd on macOS) and Ctrl+Y. Returns true when the key was used.
+pub fn handle_history_keys(
+    e: &KeyboardEvent,
+    formatter_items: Signal<Vec<FormatterItem>>,
+    history: Signal<EditHistory>,
+    selected_group_idx: Signal<Option<usize>>,
+) -> bool {
+    let modifiers = e.modifiers();
+    if !(modifiers.ctrl() || modifiers.meta()) {
+        return false;
+    }
+    let redo = match e.key() {
+        Key::Character(c) if c.eq_ignore_ascii_case("z") => modifiers.shift(),
+        Key::Character(c) if c.eq_ignore_ascii_case("y") => true,
+        _ => return false,
+    };
+    step_history(formatter_items, history, selected_group_idx, redo, 1);
+    true
+}
+
+/// `onkeydown` for text fields: keeps the key inside the field so Ctrl+Z undoes
+/// the typing rather than reaching the formatter's plan-wide undo
+pub fn keep_text_undo(e: KeyboardEvent) {
+    e.stop_propagation();
+}
+
+/// Undo / redo buttons and the list of recorded edits
+#[component]
+pub fn HistoryBar(
+    formatter_items: Signal<Vec<FormatterItem>>,
+    history: Signal<EditHistory>,
+    selected_group_idx: Signal<Option<usize>>,
+) -> Element {
+    let mut show_list = use_signal(|| false);
+    let can_undo = history.read().can_undo();
+    let can_redo = history.read().can_redo();
+    let undo_labels = history.read().undo_labels();
+    let redo_labels = history.read().redo_labels();
+
+    rsx! {
+        div { class: "px-4 py-2 border-b border-zinc-800/50 text-[0.65rem] font-bold uppercase tracking-wider",
+            div { class: "flex items-center gap-2",
+                button {
+                    class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 hover:text-cyan-500 hover:border-cyan-500/50 transition-all",
+                    title: "Undo (Ctrl+Z)",
+                    disabled: !can_undo,
+                    onclick: move |_| step_history(formatter_items, history, selected_group_idx, false, 1),
+                    "↶ UNDO"
+                }
+                button {
+                    class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 hover:text-cyan-500 hover:border-cyan-500/50 transition-all",
+                    title: "Redo (Ctrl+Shift+Z)",
+                    disabled: !can_redo,
+                    onclick: move |_| step_history(formatter_items, history, selected_group_idx, true, 1),
+                    "↷ REDO"
+                }
+                span { class: "flex-1 truncate text-zinc-500 normal-case",
+                    if let Some(last) = undo_labels.first() {
+                        "{last}"
+                    }
+                }
+                button {
+                    class: "px-2 py-1 rounded text-zinc-500 hover:text-zinc-200 transition-all",
+                    onclick: move |_| show_list.set(!show_list()),
+                    if show_list() { "HISTORY ▲" } else { "HISTORY ▼" }
+                }
+            }
+            if show_list() {
+                div { class: "mt-2 max-h-40 overflow-y-auto flex flex-col gap-0.5 normal-case font-normal scrollbar-thin scrollbar-thumb-zinc-700 scrollbar-track-transparent",
+                    // Undone edits, furthest in the future at the top
+                    for (i, label) in redo_labels.iter().enumerate().rev() {
+                        button {
+                            key: "redo-{i}",
+                            class: "text-left px-2 py-1 rounded text-zinc-600 line-through hover:bg-zinc-800 hover:text-zinc-400",
+                            title: "Redo up to here",
+                            onclick: move |_| step_history(formatter_items, history, selected_group_idx, true, i + 1),
+                            "{label}"
+                        }
+                    }
+                    div { class: "px-2 py-0.5 text-cyan-500 font-bold", "● CURRENT" }
+                    for (i, label) in undo_labels.iter().enumerate() {
+                        button {
+                            key: "undo-{i}",
+                            class: "text-left px-2 py-1 rounded text-zinc-300 hover:bg-zinc-800",
+                            title: "Undo back to before this edit",
+                            onclick: move |_| step_history(formatter_items, history, selected_group_idx, false, i + 1),
+                            "{label}"
+                        }
+                    }
+                    if undo_labels.is_empty() && redo_labels.is_empty() {
+                        div { class: "px-2 py-1 text-zinc-600", "No edits yet" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use dioxus::prelude::*;
+use crate::components::edit_items;
+use crate::types::{
+    build_import, find_playlist_match, next_group_id, AppSettings, Duration, EditHistory, FormatterItem, ImportField,
+    ImportTable, PlaylistItem,
+};
+use crate::utils::parse_duration;
+
+/// Rows shown in the preview before "and N more"
+const PREVIEW_ROWS: usize = 8;
+
+/// Map the columns of an imported CSV/TSV sheet to entry fields, preview which
+/// rows match the live playlist, then add the result to the plan
+#[component]
+pub fn ImportDialog(
+    formatter_items: Signal<Vec<FormatterItem>>,
+    history: Signal<EditHistory>,
+    table: ImportTable,
+    playlist_items: Vec<PlaylistItem>,
+    logs: Signal<Vec<String>>,
+    on_close: EventHandler<()>,
+) -> Element {
+    let initial_mapping = table.guess_mapping();
+    let mut mapping = use_signal(|| initial_mapping);
+    let mut replace = use_signal(|| false);
+
+    let current_mapping = mapping();
+    let has_title = current_mapping.contains(&ImportField::Title);
+    // Playlist matches for the preview rows, claiming items in order like the import does
+    let preview: Vec<(String, bool)> = {
+        let mut used = vec![false; playlist_items.len()];
+        table
+            .rows
+            .iter()
+            .take(PREVIEW_ROWS)
+            .map(|row| {
+                let title = table.cell(row, &current_mapping, ImportField::Title).to_string();
+                let found = find_playlist_match(&playlist_items, &used, &title);
+                if let Some(i) = found {
+                    used[i] = true;
+                }
+                (title, found.is_some())
+            })
+            .collect()
+    };
+    let more_rows = table.rows.len().saturating_sub(PREVIEW_ROWS);
+
+    let table_for_import = table.clone();
+    let import = move |_| {
+        let settings = AppSettings::load();
+        let default_duration = parse_duration(&settings.default_duration).unwrap_or(Duration::from_ms(300_000));
+        let result = build_import(&table_for_import, &mapping(), &playlist_items, &settings.rules, default_duration);
+        let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
+        {
+            let mut logs = logs.write();
+            logs.push(format!(
+                "[{}] Imported {} rows: {} matched to the playlist, {} manual",
+                timestamp,
+                result.matched + result.unmatched.len(),
+                result.matched,
+                result.unmatched.len()
+            ));
+            for warning in &result.warnings {
+                logs.push(format!("[{}] ⚠ {}", timestamp, warning));
+            }
+        }
+        let replace = replace();
+        let label = if replace { "Replace plan with import" } else { "Import sheet" };
+        edit_items(formatter_items, history, label.to_string(), move |items| {
+            if replace {
+                items.clear();
+            }
+            for mut item in result.items {
+                // Group ids only need to be unique within the combined plan
+                if let FormatterItem::Group { id, .. } = &mut item {
+                    *id = next_group_id(items);
+                }
+                items.push(item);
+            }
+        });
+        on_close.call(());
+    };
+
+    rsx! {
+        div { class: "time-picker-overlay", onclick: move |_| on_close.call(()),
+            div {
+                class: "time-picker-modal",
+                style: "width: 640px;",
+                onclick: move |e| e.stop_propagation(),
+                div { class: "tp-header", "Import {table.rows.len()} rows" }
+                div { class: "flex flex-col gap-1 max-h-[30vh] overflow-y-auto",
+                    div { class: "grid grid-cols-[1fr_140px] gap-2 px-1 text-[0.6rem] font-bold text-zinc-600 uppercase tracking-wider",
+                        span { "COLUMN" }
+                        span { "IMPORT AS" }
+                    }
+                    for (col, header) in table.headers.iter().enumerate() {
+                        div { key: "{col}", class: "grid grid-cols-[1fr_140px] gap-2 items-center text-xs font-mono",
+                            div { class: "flex flex-col min-w-0",
+                                span { class: "truncate text-zinc-300", "{header}" }
+                                span { class: "truncate text-[0.6rem] text-zinc-600",
+                                    {table.rows.first().and_then(|row| row.get(col)).cloned().unwrap_or_default()}
+                                }
+                            }
+                            select {
+                                class: "bg-zinc-950 border border-zinc-700 text-zinc-300 px-1 py-1 rounded font-mono text-xs focus:outline-none cursor-pointer",
+                                value: "{ImportField::ALL.iter().position(|f| Some(f) == current_mapping.get(col)).unwrap_or(0)}",
+                                onchange: move |e| {
+                                    let Some(field) = e.value().parse::<usize>().ok().and_then(|i| ImportField::ALL.get(i).copied()) else { return };
+                                    let mut mapping = mapping.write();
+                                    // Each field comes from one column
+                                    if field != ImportField::Ignore {
+                                        for other in mapping.iter_mut().filter(|f| **f == field) {
+                                            *other = ImportField::Ignore;
+                                        }
+                                    }
+                                    if let Some(slot) = mapping.get_mut(col) {
+                                        *slot = field;
+                                    }
+                                },
+                                for (idx, field) in ImportField::ALL.iter().enumerate() {
+                                    option { value: "{idx}", "{field.label()}" }
+                                }
+                            }
+                        }
+                    }
+                }
+                if has_title {
+                    div { class: "flex flex-col gap-1 bg-zinc-950/60 border border-zinc-800 rounded p-2 text-xs font-mono",
+                        for (idx, (title, found)) in preview.iter().enumerate() {
+                            div { key: "{idx}", class: "flex items-center gap-2",
+                                span { class: "flex-1 truncate text-zinc-300", "{title}" }
+                                if *found {
+                                    span { class: "text-[0.6rem] font-bold text-emerald-500", "PLAYLIST" }
+                                } else {
+                                    span { class: "text-[0.6rem] font-bold text-zinc-500", "MANUAL" }
+                                }
+                            }
+                        }
+                        if more_rows > 0 {
+                            div { class: "text-[0.65rem] text-zinc-600", "…and {more_rows} more" }
+                        }
+                    }
+                } else {
+                    div { class: "text-xs text-amber-500", "Pick the column that holds the entry titles" }
+                }
+                label { class: "flex items-center gap-2 text-xs text-zinc-400 cursor-pointer",
+                    input {
+                        r#type: "checkbox",
+                        class: "w-4 h-4 accent-emerald-500 cursor-pointer",
+                        checked: replace(),
+                        onchange: move |e| replace.set(e.checked()),
+                    }
+                    "Replace the current plan instead of adding to it"
+                }
+                div { class: "flex gap-2",
+                    button {
+                        class: "flex-1 py-2 rounded border border-zinc-700 text-zinc-400 text-xs font-bold hover:text-zinc-200 transition-all",
+                        onclick: move |_| on_close.call(()),
+                        "CANCEL"
+                    }
+                    button {
+                        class: "flex-1 py-2 bg-emerald-500 hover:bg-emerald-400 disabled:opacity-40 text-zinc-950 font-extrabold rounded text-xs tracking-wider uppercase transition-all",
+                        disabled: !has_title,
+                        onclick: import,
+                        "IMPORT"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use dioxus::prelude::*;
+use crate::components::{keep_text_undo, DurationInput};
+use crate::types::{AppSettings, Duration, TimeOfDay, TimedEntry};
+use crate::utils::{parse_duration, parse_end_time};
+
+const INPUT_CLASS: &str = "w-full bg-zinc-950 border border-zinc-800 rounded px-2 py-1.5 text-xs font-mono text-zinc-200 focus:outline-none focus:border-emerald-500/50 transition-all placeholder-zinc-700";
+
+/// Form for entries ProPresenter doesn't know about ("Band change", "Baptism", ...).
+/// Left empty, the duration falls back to the default. The end time is passed
+/// separately as None when left empty, for the parent to suggest one.
+#[component]
+pub fn ManualEntryForm(on_add: EventHandler<(TimedEntry, Option<TimeOfDay>)>) -> Element {
+    let mut open = use_signal(|| false);
+    let mut title = use_signal(String::new);
+    let mut duration = use_signal(|| Option::<Duration>::None);
+    let mut end_text = use_signal(String::new);
+    let mut cue = use_signal(String::new);
+    let mut note = use_signal(String::new);
+
+    if !open() {
+        return rsx! {
+            button {
+                class: "mx-4 mt-4 p-3 border-2 border-dashed border-zinc-700 rounded-lg text-zinc-500 font-mono text-xs font-bold hover:border-emerald-500/50 hover:text-emerald-500 hover:bg-emerald-500/5 transition-all w-[calc(100%-2rem)] cursor-pointer",
+                onclick: move |_| open.set(true),
+                "+ MANUAL ENTRY"
+            }
+        };
+    }
+
+    let end_time = match end_text().trim() {
+        "" => Ok(None),
+        text => parse_end_time(text).map(Some),
+    };
+    let can_add = !title().trim().is_empty() && end_time.is_ok();
+
+    rsx! {
+        div { class: "mx-4 mt-4 p-3 border border-emerald-500/30 rounded-lg bg-emerald-500/5 flex flex-col gap-2",
+            div { class: "flex items-center justify-between text-[0.65rem] font-bold text-emerald-500 uppercase tracking-wider",
+                span { "MANUAL ENTRY" }
+                button {
+                    class: "text-zinc-500 hover:text-zinc-200 transition-all",
+                    title: "Cancel",
+                    onclick: move |_| open.set(false),
+                    "✕"
+                }
+            }
+            input {
+                class: INPUT_CLASS,
+                placeholder: "Title, e.g. Band change",
+                value: "{title}",
+                onkeydown: keep_text_undo,
+                oninput: move |e| title.set(e.value()),
+            }
+            div { class: "grid grid-cols-3 gap-2",
+                DurationInput {
+                    value: duration(),
+                    placeholder: "duration",
+                    on_change: move |d| duration.set(d),
+                }
+                input {
+                    class: if end_time.is_err() { "w-full bg-zinc-950 border border-red-500/50 rounded px-2 py-1.5 text-xs font-mono text-zinc-200 focus:outline-none" } else { INPUT_CLASS },
+                    placeholder: "end time",
+                    title: end_time.as_ref().err().map(|e| e.to_string()).unwrap_or_default(),
+                    value: "{end_text}",
+                    onkeydown: keep_text_undo,
+                    oninput: move |e| end_text.set(e.value()),
+                }
+                input {
+                    class: INPUT_CLASS,
+                    placeholder: "cue",
+                    value: "{cue}",
+                    onkeydown: keep_text_undo,
+                    oninput: move |e| cue.set(e.value()),
+                }
+            }
+            input {
+                class: INPUT_CLASS,
+                placeholder: "Note",
+                value: "{note}",
+                onkeydown: keep_text_undo,
+                oninput: move |e| note.set(e.value()),
+            }
+            button {
+                class: "py-2 bg-emerald-500 hover:bg-emerald-400 disabled:opacity-40 text-zinc-950 font-extrabold rounded text-xs tracking-wider uppercase transition-all",
+                disabled: !can_add,
+                onclick: move |_| {
+                    let duration = duration().unwrap_or_else(|| {
+                        parse_duration(&AppSettings::load().default_duration).unwrap_or(Duration::from_ms(300_000))
+                    });
+                    let end_time = match end_text().trim() {
+                        "" => None,
+                        text => match parse_end_time(text) {
+                            Ok(end) => Some(end),
+                            Err(_) => return,
+                        },
+                    };
+                    let mut entry = TimedEntry::manual(title().trim().to_string(), duration, TimeOfDay::MIDNIGHT);
+                    entry.cue = cue().trim().to_string();
+                    entry.note = note().trim().to_string();
+                    on_add.call((entry, end_time));
+                    title.set(String::new());
+                    cue.set(String::new());
+                    note.set(String::new());
+                    end_text.set(String::new());
+                },
+                "ADD ENTRY"
+            }
+        }
+    }
+}
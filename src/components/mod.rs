@@ -4,8 +4,38 @@ mod navbar;
 mod settings;
 mod time_picker;
 mod formatter;
+mod project_bar;
+mod session_recovery;
+mod history;
+mod templates;
+mod apply_template;
+mod duration_input;
+mod rules_editor;
+mod bulk_edit;
+mod manual_entry;
+mod fit_dialog;
+mod rebalance;
+mod rehearsal;
+mod reports;
+mod import_dialog;
+mod export;
 
 pub use navbar::Navbar;
 pub use settings::Settings;
 pub use time_picker::TimePicker;
 pub use formatter::Formatter;
+pub use project_bar::ProjectBar;
+pub use session_recovery::SessionRecovery;
+pub use history::{edit_items, edit_items_coalesced, handle_history_keys, keep_text_undo, HistoryBar};
+pub use templates::Templates;
+pub use apply_template::ApplyTemplate;
+pub use duration_input::DurationInput;
+pub use rules_editor::RulesEditor;
+pub use bulk_edit::{select_entry, BulkEditBar};
+pub use manual_entry::ManualEntryForm;
+pub use fit_dialog::FitDialog;
+pub use rebalance::LiveRebalance;
+pub use rehearsal::RehearsalPanel;
+pub use reports::Reports;
+pub use import_dialog::ImportDialog;
+pub use export::save_export;
@@ -18,6 +18,18 @@ pub fn Navbar() -> Element {
                     active_class: "!bg-zinc-800 !text-emerald-400 shadow-sm",
                     "FORMATTER"
                 }
+                Link {
+                    to: Route::Templates {},
+                    class: "px-4 py-2 rounded text-xs font-bold text-zinc-500 hover:text-zinc-200 hover:bg-zinc-800 transition-all uppercase tracking-widest",
+                    active_class: "!bg-zinc-800 !text-emerald-400 shadow-sm",
+                    "TEMPLATES"
+                }
+                Link {
+                    to: Route::Reports {},
+                    class: "px-4 py-2 rounded text-xs font-bold text-zinc-500 hover:text-zinc-200 hover:bg-zinc-800 transition-all uppercase tracking-widest",
+                    active_class: "!bg-zinc-800 !text-emerald-400 shadow-sm",
+                    "REPORTS"
+                }
                 Link {
                     to: Route::Settings {},
                     class: "px-4 py-2 rounded text-xs font-bold text-zinc-500 hover:text-zinc-200 hover:bg-zinc-800 transition-all uppercase tracking-widest",
@@ -0,0 +1,309 @@
+use dioxus::prelude::*;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use crate::components::{edit_items, save_export};
+use crate::types::{
+    computed_schedule, next_service_day, run_sheet_date, schedule_to_csv, schedule_to_xlsx, AppSettings, EditHistory,
+    FormatterItem, ImportTable, PlaylistResponse, ProjectFile, RunSheet, PROJECT_FILE_EXTENSION,
+};
+
+/// Save / Save As / Open / Recent controls for `.pp2ot` project files
+#[component]
+pub fn ProjectBar(
+    formatter_items: Signal<Vec<FormatterItem>>,
+    history: Signal<EditHistory>,
+    selected_group_idx: Signal<Option<usize>>,
+    playlist_name: Signal<String>,
+    project_path: Signal<Option<PathBuf>>,
+    /// Items as last saved to or opened from `project_path`
+    saved_items: Signal<Vec<FormatterItem>>,
+    /// UUID of the selected ProPresenter playlist, if it's known
+    playlist_uuid: ReadSignal<Option<String>>,
+    /// Sheet the column-mapping dialog is open for
+    import_table: Signal<Option<ImportTable>>,
+    logs: Signal<Vec<String>>,
+) -> Element {
+    let mut recent_projects = use_signal(|| AppSettings::load().recent_projects);
+    // Printed in the run sheet header; plans are usually exported ahead of the service
+    let mut sheet_date = use_signal(|| next_service_day(chrono::Local::now().date_naive()));
+
+    let mut add_log = move |msg: String| {
+        let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
+        logs.write().push(format!("[{}] {}", timestamp, msg));
+    };
+
+    let mut save_to = move |path: PathBuf| {
+        let items = formatter_items.read().clone();
+        let project = ProjectFile::new(playlist_name(), playlist_uuid().unwrap_or_default(), items.clone());
+        match project.save(&path) {
+            Ok(_) => {
+                saved_items.set(items);
+                add_log(format!("✓ Saved project: {}", path.display()));
+                if let Err(e) = AppSettings::remember_project(&path) {
+                    add_log(format!("✗ Could not update recent projects: {}", e));
+                }
+                recent_projects.set(AppSettings::load().recent_projects);
+                project_path.set(Some(path));
+            }
+            Err(e) => add_log(format!("✗ Save failed: {}", e)),
+        }
+    };
+
+    let save_as = move || {
+        let suggested = format!(
+            "{}.{}",
+            if playlist_name().is_empty() { "service".to_string() } else { playlist_name() },
+            PROJECT_FILE_EXTENSION
+        );
+        spawn(async move {
+            let handle = rfd::AsyncFileDialog::new()
+                .add_filter("PP2OT Project", &[PROJECT_FILE_EXTENSION])
+                .set_file_name(suggested)
+                .save_file()
+                .await;
+            if let Some(handle) = handle {
+                let mut path = handle.path().to_path_buf();
+                if path.extension().is_none() {
+                    path.set_extension(PROJECT_FILE_EXTENSION);
+                }
+                save_to(path);
+            }
+        });
+    };
+
+    let mut open_path = move |path: PathBuf| {
+        let project = match ProjectFile::load(&path) {
+            Ok(project) => project,
+            Err(e) => {
+                add_log(format!("✗ Could not open {}: {}", path.display(), e));
+                return;
+            }
+        };
+        add_log(format!("Opened project: {} ({} items)", path.display(), project.items.len()));
+        saved_items.set(project.items.clone());
+        let opened = project.items.clone();
+        edit_items(formatter_items, history, format!("Open {}", path.display()), |items| *items = opened);
+        selected_group_idx.set(None);
+        playlist_name.set(project.playlist_name.clone());
+        if let Err(e) = AppSettings::remember_project(&path) {
+            add_log(format!("✗ Could not update recent projects: {}", e));
+        }
+        recent_projects.set(AppSettings::load().recent_projects);
+        project_path.set(Some(path));
+
+        spawn(async move {
+            verify_against_live_playlist(project, playlist_name, logs).await;
+        });
+    };
+
+    let open = move |_| {
+        spawn(async move {
+            let handle = rfd::AsyncFileDialog::new()
+                .add_filter("PP2OT Project", &[PROJECT_FILE_EXTENSION])
+                .pick_file()
+                .await;
+            if let Some(handle) = handle {
+                open_path(handle.path().to_path_buf());
+            }
+        });
+    };
+
+    let import = move |_| {
+        spawn(async move {
+            let handle = rfd::AsyncFileDialog::new()
+                .add_filter("Spreadsheet", &["csv", "tsv", "txt"])
+                .pick_file()
+                .await;
+            let Some(handle) = handle else { return };
+            let path = handle.path().to_path_buf();
+            match std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|text| ImportTable::parse(&text)) {
+                Ok(table) if table.rows.is_empty() => add_log(format!("⚠ {} has no rows to import", path.display())),
+                Ok(table) => import_table.set(Some(table)),
+                Err(e) => add_log(format!("✗ Could not import {}: {}", path.display(), e)),
+            }
+        });
+    };
+
+    let export = move |format: String| {
+        let rows = computed_schedule(&formatter_items.read());
+        let name = if playlist_name().is_empty() { "service".to_string() } else { playlist_name() };
+        let run_sheet = || RunSheet {
+            service_name: name.clone(),
+            date: run_sheet_date(sheet_date()),
+            rows: rows.clone(),
+        };
+        let (contents, extension, file_name) = match format.as_str() {
+            "csv" => (schedule_to_csv(&rows).map(String::into_bytes), "csv", name.clone()),
+            "xlsx" => (schedule_to_xlsx(&rows), "xlsx", name.clone()),
+            "html" => (Ok(run_sheet().to_html().into_bytes()), "html", format!("{} run sheet", name)),
+            "pdf" => (run_sheet().to_pdf(), "pdf", format!("{} run sheet", name)),
+            _ => return,
+        };
+        spawn(async move {
+            let result = match contents {
+                Ok(contents) => save_export(contents, &file_name, extension).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(Some(path)) => add_log(format!("✓ Exported plan: {}", path)),
+                Ok(None) => {}
+                Err(e) => add_log(format!("✗ Export failed: {}", e)),
+            }
+        });
+    };
+
+    let file_label = project_path
+        .read()
+        .as_deref()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "UNSAVED PLAN".to_string());
+    let date_value = sheet_date().format("%Y-%m-%d").to_string();
+
+    rsx! {
+        div { class: "flex items-center gap-2 px-4 py-2 border-b border-zinc-800/50 text-[0.65rem] font-bold uppercase tracking-wider",
+            span { class: "flex-1 truncate text-zinc-400 normal-case", title: "{file_label}", "{file_label}" }
+            button {
+                class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 hover:text-emerald-500 hover:border-emerald-500/50 transition-all",
+                onclick: open,
+                "OPEN"
+            }
+            button {
+                class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 hover:text-emerald-500 hover:border-emerald-500/50 transition-all",
+                onclick: move |_| {
+                    match project_path() {
+                        Some(path) => save_to(path),
+                        None => save_as(),
+                    }
+                },
+                "SAVE"
+            }
+            button {
+                class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 hover:text-emerald-500 hover:border-emerald-500/50 transition-all",
+                onclick: move |_| save_as(),
+                "SAVE AS"
+            }
+            button {
+                class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 hover:text-emerald-500 hover:border-emerald-500/50 transition-all",
+                title: "Import a CSV/TSV run sheet",
+                onclick: import,
+                "IMPORT"
+            }
+            input {
+                r#type: "date",
+                class: "bg-zinc-950 border border-zinc-700 text-zinc-400 px-1 py-0.5 rounded font-mono text-[0.65rem] focus:outline-none focus:border-emerald-500/50",
+                title: "Service date printed on the run sheet",
+                value: "{date_value}",
+                onchange: move |e| {
+                    if let Ok(date) = chrono::NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d") {
+                        sheet_date.set(date);
+                    }
+                },
+            }
+            select {
+                class: "w-24 bg-zinc-950 border border-zinc-700 text-zinc-400 px-1 py-1 rounded font-mono text-[0.65rem] focus:outline-none focus:border-emerald-500/50 cursor-pointer",
+                value: "",
+                onchange: move |e| export(e.value()),
+                option { value: "", "EXPORT" }
+                option { value: "csv", "CSV" }
+                option { value: "xlsx", title: "Laid out for Ontime's spreadsheet import", "XLSX (ONTIME)" }
+                option { value: "html", title: "Printable run sheet - open in a browser and print", "RUN SHEET (HTML)" }
+                option { value: "pdf", "RUN SHEET (PDF)" }
+            }
+            if !recent_projects.read().is_empty() {
+                select {
+                    class: "w-24 bg-zinc-950 border border-zinc-700 text-zinc-400 px-1 py-1 rounded font-mono text-[0.65rem] focus:outline-none focus:border-emerald-500/50 cursor-pointer",
+                    value: "",
+                    onchange: move |e| {
+                        let path = e.value();
+                        if !path.is_empty() {
+                            open_path(PathBuf::from(path));
+                        }
+                    },
+                    option { value: "", "RECENT" }
+                    for path in recent_projects.read().iter() {
+                        option { value: "{path}", title: "{path}",
+                            {Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.clone())}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Check a reopened plan against the live ProPresenter playlist it was built from
+async fn verify_against_live_playlist(
+    project: ProjectFile,
+    mut playlist_name: Signal<String>,
+    mut logs: Signal<Vec<String>>,
+) {
+    let mut add_log = move |msg: String| {
+        let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
+        logs.write().push(format!("[{}] {}", timestamp, msg));
+    };
+
+    if project.playlist_uuid.is_empty() {
+        add_log("⚠ Project has no source playlist UUID - skipping playlist check".to_string());
+        return;
+    }
+
+    let settings = AppSettings::load();
+    let url = format!(
+        "http://{}:{}/v1/playlist/{}",
+        settings.pp_host, settings.pp_port, project.playlist_uuid
+    );
+    let client = reqwest::Client::new();
+    let live = match client.get(&url).header("accept", "application/json").send().await {
+        Ok(response) if response.status().is_success() => response.json::<PlaylistResponse>().await.ok(),
+        _ => None,
+    };
+    let Some(live) = live else {
+        add_log(format!(
+            "⚠ Source playlist \"{}\" ({}) not found in ProPresenter",
+            project.playlist_name, project.playlist_uuid
+        ));
+        return;
+    };
+
+    if live.id.name != project.playlist_name {
+        add_log(format!(
+            "⚠ Source playlist was renamed: \"{}\" → \"{}\"",
+            project.playlist_name, live.id.name
+        ));
+        playlist_name.set(live.id.name.clone());
+    }
+
+    let live_ids: HashSet<&str> = live.items.iter().map(|item| item.id.uuid.as_str()).collect();
+    let planned: Vec<(&str, &str)> = project
+        .items
+        .iter()
+        .flat_map(|item| match item {
+            FormatterItem::Standalone(entry) => vec![entry],
+            FormatterItem::Group { entries, .. } => entries.iter().collect(),
+            FormatterItem::Reference { .. } | FormatterItem::Delay { .. } | FormatterItem::Milestone { .. } => vec![],
+        })
+        .filter(|entry| !entry.manual)
+        .map(|entry| (entry.item_id.as_str(), entry.name.as_str()))
+        .collect();
+    let missing: Vec<&str> = planned
+        .iter()
+        .filter(|(id, _)| !live_ids.contains(id))
+        .map(|(_, name)| *name)
+        .collect();
+    let planned_ids: HashSet<&str> = planned.iter().map(|(id, _)| *id).collect();
+    let unplanned = live_ids.iter().filter(|id| !planned_ids.contains(*id)).count();
+
+    if missing.is_empty() {
+        add_log(format!("✓ Plan matches live playlist \"{}\"", live.id.name));
+    } else {
+        add_log(format!(
+            "⚠ {} plan entries are no longer in the playlist: {}",
+            missing.len(),
+            missing.join(", ")
+        ));
+    }
+    if unplanned > 0 {
+        add_log(format!("{} playlist items are not in the plan", unplanned));
+    }
+}
@@ -0,0 +1,179 @@
+use dioxus::prelude::*;
+use crate::types::{propose_trims, AppSettings, OntimeRundown, RuntimeOffset, TrimProposal};
+
+/// How often the runtime offset is polled while live mode is on
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+fn ontime_base_url() -> String {
+    let settings = AppSettings::load();
+    format!("http://{}:{}", settings.ot_host, settings.ot_port)
+}
+
+async fn fetch_offset(client: &reqwest::Client) -> Result<RuntimeOffset, String> {
+    let json = client
+        .get(format!("{}/api/poll", ontime_base_url()))
+        .send()
+        .await
+        .map_err(|e| format!("Poll failed: {}", e))?
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| format!("Bad poll response: {}", e))?;
+    RuntimeOffset::from_poll(&json).ok_or_else(|| "Poll response has no offset".to_string())
+}
+
+async fn fetch_rundown(client: &reqwest::Client) -> Result<OntimeRundown, String> {
+    client
+        .get(format!("{}/data/rundowns/current", ontime_base_url()))
+        .header("accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("Rundown request failed: {}", e))?
+        .json::<OntimeRundown>()
+        .await
+        .map_err(|e| format!("Bad rundown response: {}", e))
+}
+
+/// Live assistant: watches how far behind the show is and proposes trims to
+/// the remaining events so the next locked-end event still starts on time
+#[component]
+pub fn LiveRebalance(logs: Signal<Vec<String>>) -> Element {
+    let mut live = use_signal(|| false);
+    let mut offset = use_signal(|| Option::<RuntimeOffset>::None);
+    let mut error = use_signal(|| Option::<String>::None);
+    // Anchor title and trims awaiting approval
+    let mut proposal = use_signal(|| Option::<(String, Vec<TrimProposal>)>::None);
+    let mut busy = use_signal(|| false);
+
+    let mut add_log = move |msg: String| {
+        let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
+        logs.write().push(format!("[{}] {}", timestamp, msg));
+    };
+
+    use_future(move || async move {
+        let client = reqwest::Client::new();
+        loop {
+            if live() {
+                match fetch_offset(&client).await {
+                    Ok(current) => {
+                        offset.set(Some(current));
+                        error.set(None);
+                    }
+                    Err(e) => error.set(Some(e)),
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+
+    let propose = move |_| {
+        busy.set(true);
+        spawn(async move {
+            let client = reqwest::Client::new();
+            let result = async {
+                let current = fetch_offset(&client).await?;
+                offset.set(Some(current.clone()));
+                let behind = current.behind().ok_or("Not running behind - nothing to trim")?;
+                let rundown = fetch_rundown(&client).await?;
+                let (anchor, trims) = propose_trims(&rundown, current.current_event_id.as_deref(), behind)?;
+                Ok::<_, String>((anchor, trims))
+            }
+            .await;
+            match result {
+                Ok(found) => {
+                    proposal.set(Some(found));
+                    error.set(None);
+                }
+                Err(e) => {
+                    proposal.set(None);
+                    error.set(Some(e));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    let apply = move |_| {
+        let Some((anchor, trims)) = proposal.take() else { return };
+        busy.set(true);
+        spawn(async move {
+            let client = reqwest::Client::new();
+            let base_url = ontime_base_url();
+            add_log(format!("Rebalancing {} events to meet {}", trims.len(), anchor));
+            for trim in &trims {
+                // Ontime recalculates the rundown after each duration change
+                let url = format!("{}/api/change/{}/duration/{}", base_url, trim.event_id, trim.proposed.as_ms());
+                match client.get(&url).send().await {
+                    Ok(resp) if resp.status().is_success() => {
+                        add_log(format!("✓ {}: {} → {}", trim.title, trim.current, trim.proposed));
+                    }
+                    Ok(resp) => add_log(format!("✗ {}: Error {}", trim.title, resp.status())),
+                    Err(e) => add_log(format!("✗ {}: {}", trim.title, e)),
+                }
+            }
+            if let Ok(current) = fetch_offset(&client).await {
+                offset.set(Some(current));
+            }
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        div { class: "border-t border-zinc-800/80 p-3 flex flex-col gap-2 text-xs",
+            div { class: "flex items-center gap-2",
+                span { class: "font-extrabold tracking-widest text-zinc-500 uppercase flex-1", "LIVE REBALANCE" }
+                match offset() {
+                    Some(current) => match current.behind() {
+                        Some(behind) => rsx! {
+                            span { class: "font-mono font-bold text-red-500", "-{behind} behind" }
+                        },
+                        None => rsx! {
+                            span { class: "font-mono font-bold text-emerald-500", "on time" }
+                        },
+                    },
+                    None => rsx! {},
+                }
+                button {
+                    class: if live() { "px-2 py-1 rounded border border-emerald-500/50 text-emerald-500 bg-emerald-500/10 text-[0.65rem] font-bold" } else { "px-2 py-1 rounded border border-zinc-700 text-zinc-500 text-[0.65rem] font-bold hover:text-zinc-300" },
+                    title: "Poll Ontime's runtime offset every few seconds",
+                    onclick: move |_| live.set(!live()),
+                    if live() { "LIVE" } else { "OFF" }
+                }
+                button {
+                    class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 text-[0.65rem] font-bold hover:text-cyan-500 hover:border-cyan-500/50 transition-all disabled:opacity-40",
+                    disabled: busy(),
+                    onclick: propose,
+                    "PROPOSE"
+                }
+            }
+            if let Some(e) = error() {
+                div { class: "text-[0.7rem] text-amber-500", "{e}" }
+            }
+            if let Some((anchor, trims)) = proposal() {
+                div { class: "flex flex-col gap-1 bg-zinc-950/60 border border-zinc-800 rounded p-2",
+                    div { class: "text-[0.65rem] text-zinc-500", "Trims to start {anchor} on time:" }
+                    for trim in trims.iter() {
+                        div { class: "flex items-center gap-2 font-mono",
+                            span { class: "flex-1 truncate text-zinc-300 font-sans", "{trim.title}" }
+                            span { class: "text-zinc-500", "{trim.current}" }
+                            span { class: "text-zinc-600", "→" }
+                            span { class: "text-amber-500", "{trim.proposed}" }
+                        }
+                    }
+                    div { class: "flex gap-2 mt-1",
+                        button {
+                            class: "flex-1 py-1.5 rounded border border-zinc-700 text-zinc-400 text-[0.65rem] font-bold hover:text-zinc-200",
+                            onclick: move |_| proposal.set(None),
+                            "DISMISS"
+                        }
+                        button {
+                            class: "flex-1 py-1.5 rounded bg-cyan-600 hover:bg-cyan-500 text-white text-[0.65rem] font-bold disabled:opacity-40",
+                            disabled: busy(),
+                            onclick: apply,
+                            "APPLY TO ONTIME"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
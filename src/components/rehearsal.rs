@@ -0,0 +1,203 @@
+use dioxus::prelude::*;
+use crate::components::edit_items;
+use crate::types::{
+    apply_takes_to_items, apply_takes_to_template, AppSettings, EditHistory, FormatterItem, LiveItem,
+    RehearsalRecorder, RehearsalSource, ServiceTemplate, TimingHistory,
+};
+
+/// How often the live item is checked while recording
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+async fn fetch_json(client: &reqwest::Client, url: String, source: RehearsalSource) -> Result<serde_json::Value, String> {
+    client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("{} unreachable: {}", source.label(), e))?
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| format!("Bad {} response: {}", source.label(), e))
+}
+
+/// Ask `source` what is live right now
+async fn fetch_live(client: &reqwest::Client, source: RehearsalSource) -> Result<Option<LiveItem>, String> {
+    let settings = AppSettings::load();
+    match source {
+        RehearsalSource::ProPresenter => {
+            let base_url = format!("http://{}:{}", settings.pp_host, settings.pp_port);
+            let playlist = fetch_json(client, format!("{}/v1/playlist/active", base_url), source).await?;
+            // Only needed for the timing history, so a failure here isn't fatal
+            let presentation = fetch_json(client, format!("{}/v1/presentation/active", base_url), source).await.ok();
+            Ok(LiveItem::from_propresenter(&playlist, presentation.as_ref()))
+        }
+        RehearsalSource::Ontime => {
+            let poll = fetch_json(client, format!("http://{}:{}/api/poll", settings.ot_host, settings.ot_port), source).await?;
+            Ok(LiveItem::from_ontime_poll(&poll))
+        }
+    }
+}
+
+/// Rehearsal mode: records how long each item was actually live during a
+/// run-through, then writes those durations into the plan or a template
+#[component]
+pub fn RehearsalPanel(
+    formatter_items: Signal<Vec<FormatterItem>>,
+    history: Signal<EditHistory>,
+    timing_history: Signal<TimingHistory>,
+    logs: Signal<Vec<String>>,
+) -> Element {
+    let mut source = use_signal(|| RehearsalSource::ProPresenter);
+    let mut recording = use_signal(|| false);
+    let mut recorder = use_signal(RehearsalRecorder::default);
+    let mut error = use_signal(|| Option::<String>::None);
+    let mut template_idx = use_signal(|| Option::<usize>::None);
+    let templates = use_signal(ServiceTemplate::load_all);
+
+    let mut add_log = move |msg: String| {
+        let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
+        logs.write().push(format!("[{}] {}", timestamp, msg));
+    };
+
+    use_future(move || async move {
+        let client = reqwest::Client::new();
+        loop {
+            if recording() {
+                match fetch_live(&client, source()).await {
+                    // Stopped while the request was in flight
+                    Ok(_) if !recording() => {}
+                    Ok(live) => {
+                        recorder.write().observe(live, now_ms());
+                        error.set(None);
+                    }
+                    Err(e) => error.set(Some(e)),
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+
+    let takes = recorder.read().takes.clone();
+    let live_name = recorder.read().current().map(|item| item.name.clone());
+
+    rsx! {
+        div { class: "border-t border-zinc-800/80 p-3 flex flex-col gap-2 text-xs",
+            div { class: "flex items-center gap-2",
+                span { class: "font-extrabold tracking-widest text-zinc-500 uppercase flex-1", "REHEARSAL" }
+                select {
+                    class: "bg-zinc-950 border border-zinc-700 text-zinc-400 px-1 py-1 rounded font-mono text-[0.65rem] focus:outline-none cursor-pointer disabled:opacity-40",
+                    disabled: recording(),
+                    value: if source() == RehearsalSource::Ontime { "ontime" } else { "pp" },
+                    onchange: move |e| source.set(if e.value() == "ontime" { RehearsalSource::Ontime } else { RehearsalSource::ProPresenter }),
+                    option { value: "pp", "ProPresenter" }
+                    option { value: "ontime", "Ontime" }
+                }
+                button {
+                    class: if recording() { "px-2 py-1 rounded border border-red-500/50 text-red-500 bg-red-500/10 text-[0.65rem] font-bold animate-pulse" } else { "px-2 py-1 rounded border border-zinc-700 text-zinc-400 text-[0.65rem] font-bold hover:text-red-500 hover:border-red-500/50 transition-all" },
+                    onclick: move |_| {
+                        if recording() {
+                            recording.set(false);
+                            recorder.write().finish(now_ms());
+                            add_log(format!("Rehearsal stopped: {} items timed", recorder.read().takes.len()));
+                        } else {
+                            recorder.set(RehearsalRecorder::default());
+                            recording.set(true);
+                            add_log(format!("Rehearsal recording from {}", source().label()));
+                        }
+                    },
+                    if recording() { "■ STOP" } else { "● REC" }
+                }
+            }
+            if let Some(e) = error() {
+                div { class: "text-[0.7rem] text-amber-500", "{e}" }
+            }
+            if !takes.is_empty() || live_name.is_some() {
+                div { class: "flex flex-col gap-1 bg-zinc-950/60 border border-zinc-800 rounded p-2 max-h-48 overflow-y-auto",
+                    for take in takes.iter() {
+                        div { class: "flex items-center gap-2 font-mono",
+                            span { class: "flex-1 truncate text-zinc-300 font-sans", "{take.name}" }
+                            span { class: "text-cyan-500", "{take.duration}" }
+                        }
+                    }
+                    if let Some(name) = live_name {
+                        div { class: "flex items-center gap-2 font-mono",
+                            span { class: "flex-1 truncate text-zinc-300 font-sans", "{name}" }
+                            span { class: "text-red-500 text-[0.6rem] font-bold", "LIVE" }
+                        }
+                    }
+                }
+            }
+            if !recording() && !takes.is_empty() {
+                div { class: "flex flex-wrap items-center gap-2",
+                    button {
+                        class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 text-[0.65rem] font-bold hover:text-emerald-500 hover:border-emerald-500/50 transition-all",
+                        title: "Set matching formatter entries to the rehearsed durations",
+                        onclick: move |_| {
+                            let takes = recorder.read().takes.clone();
+                            let mut updated = 0;
+                            edit_items(formatter_items, history, "Apply rehearsal timings".to_string(), |items| {
+                                updated = apply_takes_to_items(items, &takes);
+                            });
+                            add_log(format!("Rehearsal timings applied to {} entries", updated));
+                        },
+                        "APPLY TO PLAN"
+                    }
+                    button {
+                        class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 text-[0.65rem] font-bold hover:text-emerald-500 hover:border-emerald-500/50 transition-all",
+                        title: "Keep these timings to suggest durations next time",
+                        onclick: move |_| {
+                            let takes = recorder.read().takes.clone();
+                            let recorded = {
+                                let mut store = timing_history.write();
+                                let recorded = takes
+                                    .iter()
+                                    .filter(|t| store.record(t.presentation_uuid.as_deref(), &t.name, t.duration))
+                                    .count();
+                                store.save().map(|()| recorded)
+                            };
+                            match recorded {
+                                Ok(recorded) => add_log(format!("Recorded {} timings in the history", recorded)),
+                                Err(e) => add_log(format!("✗ Failed to save timing history: {}", e)),
+                            }
+                        },
+                        "SAVE TO HISTORY"
+                    }
+                    select {
+                        class: "bg-zinc-950 border border-zinc-700 text-zinc-400 px-1 py-1 rounded font-mono text-[0.65rem] focus:outline-none cursor-pointer",
+                        value: template_idx().map(|i| i.to_string()).unwrap_or_default(),
+                        onchange: move |e| template_idx.set(e.value().parse().ok()),
+                        option { value: "", "TEMPLATE…" }
+                        for (idx, template) in templates.read().iter().enumerate() {
+                            option { value: "{idx}", "{template.name}" }
+                        }
+                    }
+                    button {
+                        class: "px-2 py-1 rounded border border-zinc-700 text-zinc-400 text-[0.65rem] font-bold hover:text-emerald-500 hover:border-emerald-500/50 transition-all disabled:opacity-40",
+                        disabled: template_idx().is_none(),
+                        title: "Set the template's slot durations to the rehearsed ones",
+                        onclick: move |_| {
+                            let Some(idx) = template_idx() else { return };
+                            let mut all = ServiceTemplate::load_all();
+                            let Some(template) = all.get_mut(idx) else { return };
+                            let updated = apply_takes_to_template(template, &recorder.read().takes);
+                            let name = template.name.clone();
+                            match ServiceTemplate::save_all(&all) {
+                                Ok(()) => add_log(format!("Rehearsal timings saved to {} slots of {}", updated, name)),
+                                Err(e) => add_log(format!("✗ Failed to save template {}: {}", name, e)),
+                            }
+                        },
+                        "SAVE TO TEMPLATE"
+                    }
+                    button {
+                        class: "ml-auto px-2 py-1 rounded text-zinc-500 text-[0.65rem] font-bold hover:text-zinc-200 transition-all",
+                        onclick: move |_| recorder.set(RehearsalRecorder::default()),
+                        "CLEAR"
+                    }
+                }
+            }
+        }
+    }
+}
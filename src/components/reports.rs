@@ -0,0 +1,242 @@
+use dioxus::prelude::*;
+use crate::components::save_export;
+use crate::types::{format_signed_ms, AppSettings, OntimeReport, OntimeRundown, ShowReport, TimingHistory};
+use crate::utils::format_ms_to_duration;
+
+const BUTTON_CLASS: &str = "px-3 py-2 rounded border border-zinc-700 text-zinc-400 text-xs font-bold uppercase tracking-wider hover:text-emerald-500 hover:border-emerald-500/50 transition-all disabled:opacity-40";
+
+/// Text colour for a variance: red when over or late, green when under or early
+fn variance_class(ms: i64) -> &'static str {
+    match ms {
+        ms if ms > 0 => "text-red-500",
+        ms if ms < 0 => "text-emerald-500",
+        _ => "text-zinc-500",
+    }
+}
+
+async fn fetch_report() -> Result<ShowReport, String> {
+    let settings = AppSettings::load();
+    let base_url = format!("http://{}:{}", settings.ot_host, settings.ot_port);
+    let client = reqwest::Client::new();
+    let rundown = client
+        .get(format!("{}/data/rundowns/current", base_url))
+        .header("accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("Rundown request failed: {}", e))?
+        .json::<OntimeRundown>()
+        .await
+        .map_err(|e| format!("Bad rundown response: {}", e))?;
+    let report = client
+        .get(format!("{}/data/report", base_url))
+        .header("accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("Report request failed: {}", e))?
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| format!("Bad report response: {}", e))?;
+    // Some versions wrap the report in a payload object
+    let report: OntimeReport = serde_json::from_value(report.get("payload").cloned().unwrap_or(report))
+        .map_err(|e| format!("Bad report response: {}", e))?;
+    Ok(ShowReport::build(&rundown, &report))
+}
+
+/// Reports page - planned vs actual timings of the last show, from Ontime's session report
+#[component]
+pub fn Reports() -> Element {
+    let mut report = use_signal(|| Option::<ShowReport>::None);
+    let mut status = use_signal(String::new);
+    let mut loading = use_signal(|| false);
+    // Each report goes into the timing history at most once
+    let mut saved_to_history = use_signal(|| false);
+
+    let generate = move |_| {
+        loading.set(true);
+        spawn(async move {
+            match fetch_report().await {
+                Ok(built) => {
+                    status.set(format!("Report generated {}", built.generated_at));
+                    report.set(Some(built));
+                    saved_to_history.set(false);
+                }
+                Err(e) => status.set(format!("✗ {}", e)),
+            }
+            loading.set(false);
+        });
+    };
+
+    let save = move |html: bool| {
+        let Some(current) = report() else { return };
+        let contents = if html {
+            Ok(current.to_html())
+        } else {
+            current.to_csv()
+        };
+        spawn(async move {
+            let name = format!("report-{}", current.rundown_title.replace(['/', '\\'], "-"));
+            let result = match contents {
+                Ok(contents) => save_export(contents, &name, if html { "html" } else { "csv" }).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(Some(path)) => status.set(format!("Exported to {}", path)),
+                Ok(None) => {}
+                Err(e) => status.set(format!("✗ Export failed: {}", e)),
+            }
+        });
+    };
+
+    // Ontime only knows event titles, so the history matches these by name
+    let save_to_history = move |_| {
+        let Some(current) = report() else { return };
+        let mut history = TimingHistory::load();
+        let recorded = current
+            .events
+            .iter()
+            .filter_map(|event| event.actual_duration.map(|d| (event, d)))
+            .filter(|(event, duration)| history.record(None, &event.title, *duration))
+            .count();
+        match history.save() {
+            Ok(()) => {
+                saved_to_history.set(true);
+                status.set(format!("Recorded {} timings in the history", recorded));
+            }
+            Err(e) => status.set(format!("✗ Failed to save timing history: {}", e)),
+        }
+    };
+
+    rsx! {
+        div { class: "p-8 max-w-6xl w-full mx-auto flex flex-col gap-6 font-mono text-white bg-zinc-950 h-full overflow-y-auto",
+            div { class: "flex justify-between items-end mb-2",
+                h1 { class: "text-2xl font-extrabold tracking-wider text-zinc-100 uppercase", "SHOW REPORT" }
+                div { class: "flex gap-2",
+                    button {
+                        class: BUTTON_CLASS,
+                        disabled: report().is_none() || saved_to_history(),
+                        title: "Keep the actual durations to suggest timings next time",
+                        onclick: save_to_history,
+                        "SAVE TO HISTORY"
+                    }
+                    button {
+                        class: BUTTON_CLASS,
+                        disabled: report().is_none(),
+                        onclick: move |_| save(false),
+                        "EXPORT CSV"
+                    }
+                    button {
+                        class: BUTTON_CLASS,
+                        disabled: report().is_none(),
+                        onclick: move |_| save(true),
+                        "EXPORT HTML"
+                    }
+                    button {
+                        class: "px-3 py-2 bg-emerald-500 hover:bg-emerald-400 disabled:opacity-40 text-zinc-950 font-extrabold rounded text-xs tracking-wider uppercase transition-all",
+                        disabled: loading(),
+                        onclick: generate,
+                        if loading() { "LOADING..." } else { "GENERATE FROM ONTIME" }
+                    }
+                }
+            }
+            if !status().is_empty() {
+                div { class: "text-xs text-zinc-500", "{status}" }
+            }
+
+            match report() {
+                None => rsx! {
+                    div { class: "text-sm text-zinc-600",
+                        "Run the show in Ontime, then generate a report to compare the pushed plan with what actually happened."
+                    }
+                },
+                Some(current) => rsx! {
+                    div { class: "grid grid-cols-3 gap-4",
+                        div { class: "bg-zinc-900 border border-zinc-800 rounded-lg p-4 flex flex-col gap-1",
+                            span { class: "text-[0.65rem] font-bold text-zinc-500 uppercase tracking-widest", "RUNDOWN" }
+                            span { class: "text-lg font-bold text-zinc-100 truncate", "{current.rundown_title}" }
+                        }
+                        div { class: "bg-zinc-900 border border-zinc-800 rounded-lg p-4 flex flex-col gap-1",
+                            span { class: "text-[0.65rem] font-bold text-zinc-500 uppercase tracking-widest", "EVENTS RUN" }
+                            span { class: "text-lg font-bold text-zinc-100", "{current.events_run()} / {current.events.len()}" }
+                        }
+                        div { class: "bg-zinc-900 border border-zinc-800 rounded-lg p-4 flex flex-col gap-1",
+                            span { class: "text-[0.65rem] font-bold text-zinc-500 uppercase tracking-widest", "TOTAL OVERRUN" }
+                            match current.total_overrun() {
+                                Some(overrun) => rsx! {
+                                    span { class: "text-lg font-bold {variance_class(overrun)}", "{format_signed_ms(overrun)}" }
+                                },
+                                None => rsx! {
+                                    span { class: "text-lg font-bold text-zinc-600", "—" }
+                                },
+                            }
+                        }
+                    }
+
+                    if !current.groups.is_empty() {
+                        div { class: "bg-zinc-900 border border-zinc-800 rounded-lg overflow-hidden",
+                            div { class: "grid grid-cols-[1fr_90px_100px_100px_100px] gap-2 px-4 py-2 text-[0.65rem] font-bold text-zinc-500 uppercase tracking-wider border-b border-zinc-800 bg-zinc-900/50",
+                                span { "GROUP" }
+                                span { "RUN" }
+                                span { "PLANNED" }
+                                span { "ACTUAL" }
+                                span { "VARIANCE" }
+                            }
+                            for group in current.groups.iter() {
+                                div { class: "grid grid-cols-[1fr_90px_100px_100px_100px] gap-2 px-4 py-2 text-xs border-b border-zinc-800/50",
+                                    span { class: "truncate text-zinc-200", "{group.title}" }
+                                    span { class: "text-zinc-500", "{group.events_run}" }
+                                    span { class: "text-zinc-400", "{group.planned_duration}" }
+                                    span { class: "text-zinc-400", "{group.actual_duration}" }
+                                    span { class: variance_class(group.variance()), "{format_signed_ms(group.variance())}" }
+                                }
+                            }
+                        }
+                    }
+
+                    div { class: "bg-zinc-900 border border-zinc-800 rounded-lg overflow-hidden",
+                        div { class: "grid grid-cols-[60px_1fr_90px_90px_100px_90px_90px_100px] gap-2 px-4 py-2 text-[0.65rem] font-bold text-zinc-500 uppercase tracking-wider border-b border-zinc-800 bg-zinc-900/50",
+                            span { "CUE" }
+                            span { "EVENT" }
+                            span { "PLANNED" }
+                            span { "STARTED" }
+                            span { "DRIFT" }
+                            span { "PLANNED" }
+                            span { "ACTUAL" }
+                            span { "VARIANCE" }
+                        }
+                        for event in current.events.iter() {
+                            div { class: if event.actual_start.is_some() { "grid grid-cols-[60px_1fr_90px_90px_100px_90px_90px_100px] gap-2 px-4 py-2 text-xs border-b border-zinc-800/50" } else { "grid grid-cols-[60px_1fr_90px_90px_100px_90px_90px_100px] gap-2 px-4 py-2 text-xs border-b border-zinc-800/50 opacity-40" },
+                                span { class: "text-zinc-500 truncate", "{event.cue}" }
+                                div { class: "flex flex-col min-w-0",
+                                    span { class: "truncate text-zinc-200", "{event.title}" }
+                                    if let Some(group) = &event.group {
+                                        span { class: "truncate text-[0.6rem] text-zinc-600", "{group}" }
+                                    }
+                                }
+                                span { class: "text-zinc-400", "{format_ms_to_duration(event.planned_start)}" }
+                                span { class: "text-zinc-400", {event.actual_start.map(format_ms_to_duration).unwrap_or_else(|| "—".to_string())} }
+                                match event.start_drift() {
+                                    Some(drift) => rsx! {
+                                        span { class: variance_class(drift), "{format_signed_ms(drift)}" }
+                                    },
+                                    None => rsx! {
+                                        span { class: "text-zinc-700", "—" }
+                                    },
+                                }
+                                span { class: "text-zinc-400", "{event.planned_duration}" }
+                                span { class: "text-zinc-400", {event.actual_duration.map(|d| d.to_string()).unwrap_or_else(|| "—".to_string())} }
+                                match event.duration_variance() {
+                                    Some(variance) => rsx! {
+                                        span { class: variance_class(variance), "{format_signed_ms(variance)}" }
+                                    },
+                                    None => rsx! {
+                                        span { class: "text-zinc-700", "—" }
+                                    },
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
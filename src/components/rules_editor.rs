@@ -0,0 +1,189 @@
+use dioxus::prelude::*;
+use crate::components::DurationInput;
+use crate::types::{matching_rule, AppSettings, Dictionary, Duration, NameRule, PlaylistItem, RuleMatchKind, RuleSet};
+
+const INPUT_CLASS: &str = "w-full bg-zinc-950 border border-zinc-800 rounded px-2 py-1.5 text-xs font-mono text-zinc-200 focus:outline-none focus:border-emerald-500/50 transition-all placeholder-zinc-700";
+const ICON_BUTTON_CLASS: &str = "w-6 h-6 flex items-center justify-center rounded text-zinc-600 hover:text-zinc-200 hover:bg-zinc-800 transition-all";
+
+fn kind_key(kind: RuleMatchKind) -> &'static str {
+    match kind {
+        RuleMatchKind::Regex => "regex",
+        RuleMatchKind::Contains => "contains",
+        RuleMatchKind::ItemType => "type",
+    }
+}
+
+/// Ordered name-pattern rules, edited as part of the settings page
+#[component]
+pub fn RulesEditor(settings: Signal<AppSettings>) -> Element {
+    let mut test_name = use_signal(String::new);
+    let mut test_type = use_signal(|| "presentation".to_string());
+
+    let rules = settings.read().rules.clone();
+    let compiled = use_memo(move || RuleSet::new(&settings.read().rules));
+    let test_item = PlaylistItem {
+        id: Dictionary { uuid: String::new(), name: test_name(), index: 0 },
+        item_type: test_type(),
+        duration: None,
+        presentation_info: None,
+    };
+    let test_result = if test_name().is_empty() {
+        None
+    } else {
+        Some(matching_rule(&compiled.read(), &test_item).map(|(idx, rule)| (idx, rule.summary())))
+    };
+
+    rsx! {
+        div { class: "bg-zinc-900 border border-zinc-800 rounded-lg overflow-hidden flex flex-col",
+            div { class: "p-4 flex items-center gap-3 border-b border-zinc-800 bg-zinc-900/50",
+                div { class: "w-2 h-2 rounded-full bg-zinc-600" }
+                span { class: "text-xs font-bold text-zinc-500 uppercase tracking-widest flex-1", "NAME RULES" }
+                span { class: "text-[0.65rem] text-zinc-600", "First matching rule wins" }
+            }
+            div { class: "p-4 flex flex-col gap-2",
+                div { class: "grid grid-cols-[28px_110px_1fr_90px_52px_90px_80px_1fr_56px] gap-2 px-1 text-[0.6rem] font-bold text-zinc-600 uppercase tracking-wider",
+                    span { "#" }
+                    span { "MATCH" }
+                    span { "PATTERN" }
+                    span { "DURATION" }
+                    span { title: "Use the media item's own length", "MEDIA" }
+                    span { "COUNT" }
+                    span { "COLOUR" }
+                    span { "GROUP" }
+                    span {}
+                }
+                for (idx, rule) in rules.iter().enumerate() {
+                    div { key: "{idx}", class: "flex flex-col gap-1",
+                        div { class: "grid grid-cols-[28px_110px_1fr_90px_52px_90px_80px_1fr_56px] gap-2 items-center",
+                            span { class: "text-xs font-bold text-cyan-500", "R{idx + 1}" }
+                            select {
+                                class: INPUT_CLASS,
+                                value: kind_key(rule.kind),
+                                onchange: move |e| {
+                                    if let Some(kind) = RuleMatchKind::ALL.into_iter().find(|k| kind_key(*k) == e.value()) {
+                                        settings.write().rules[idx].kind = kind;
+                                    }
+                                },
+                                for kind in RuleMatchKind::ALL {
+                                    option { value: kind_key(kind), "{kind.label()}" }
+                                }
+                            }
+                            input {
+                                class: if rule.pattern_error().is_some() { "w-full bg-zinc-950 border border-red-500/50 rounded px-2 py-1.5 text-xs font-mono text-zinc-200 focus:outline-none focus:border-red-500" } else { INPUT_CLASS },
+                                placeholder: match rule.kind {
+                                    RuleMatchKind::Regex => "^Countdown",
+                                    RuleMatchKind::Contains => "Offering",
+                                    RuleMatchKind::ItemType => "media",
+                                },
+                                value: "{rule.pattern}",
+                                oninput: move |e| settings.write().rules[idx].pattern = e.value(),
+                            }
+                            DurationInput {
+                                value: rule.duration,
+                                placeholder: "keep",
+                                on_change: move |d: Option<Duration>| settings.write().rules[idx].duration = d,
+                            }
+                            input {
+                                r#type: "checkbox",
+                                class: "w-4 h-4 accent-emerald-500 cursor-pointer",
+                                checked: rule.use_media_length,
+                                onchange: move |e| settings.write().rules[idx].use_media_length = e.checked(),
+                            }
+                            select {
+                                class: INPUT_CLASS,
+                                value: match rule.count_to_end {
+                                    None => "keep",
+                                    Some(true) => "end",
+                                    Some(false) => "down",
+                                },
+                                onchange: move |e| {
+                                    settings.write().rules[idx].count_to_end = match e.value().as_str() {
+                                        "end" => Some(true),
+                                        "down" => Some(false),
+                                        _ => None,
+                                    };
+                                },
+                                option { value: "keep", "keep" }
+                                option { value: "end", "to end" }
+                                option { value: "down", "down" }
+                            }
+                            div { class: "flex items-center gap-1",
+                                input {
+                                    r#type: "color",
+                                    class: "w-8 h-7 bg-transparent cursor-pointer",
+                                    value: if rule.color.is_empty() { "#000000".to_string() } else { rule.color.clone() },
+                                    oninput: move |e| settings.write().rules[idx].color = e.value(),
+                                }
+                                if !rule.color.is_empty() {
+                                    button {
+                                        class: ICON_BUTTON_CLASS,
+                                        title: "No colour",
+                                        onclick: move |_| settings.write().rules[idx].color.clear(),
+                                        "×"
+                                    }
+                                }
+                            }
+                            input {
+                                class: INPUT_CLASS,
+                                placeholder: "keep",
+                                value: "{rule.group}",
+                                oninput: move |e| settings.write().rules[idx].group = e.value(),
+                            }
+                            div { class: "flex items-center",
+                                button {
+                                    class: ICON_BUTTON_CLASS,
+                                    title: "Move up",
+                                    disabled: idx == 0,
+                                    onclick: move |_| settings.write().rules.swap(idx - 1, idx),
+                                    "↑"
+                                }
+                                button {
+                                    class: "w-6 h-6 flex items-center justify-center rounded text-zinc-600 hover:text-red-500 hover:bg-red-500/10 transition-all",
+                                    title: "Remove rule",
+                                    onclick: move |_| {
+                                        settings.write().rules.remove(idx);
+                                    },
+                                    "×"
+                                }
+                            }
+                        }
+                        if let Some(err) = rule.pattern_error() {
+                            div { class: "text-[0.7rem] text-red-500 pl-9", "{err}" }
+                        }
+                    }
+                }
+                button {
+                    class: "self-start px-2 py-1 rounded border border-zinc-700 text-zinc-400 text-[0.65rem] font-bold uppercase tracking-wider hover:text-emerald-500 hover:border-emerald-500/50 transition-all",
+                    onclick: move |_| settings.write().rules.push(NameRule::new(RuleMatchKind::Contains)),
+                    "+ RULE"
+                }
+
+                // Try a name against the rules before saving
+                div { class: "mt-2 pt-3 border-t border-zinc-800/50 flex items-center gap-2",
+                    span { class: "text-[0.65rem] font-bold text-zinc-500 uppercase tracking-wider", "TEST" }
+                    input {
+                        class: "flex-1 {INPUT_CLASS}",
+                        placeholder: "Item name",
+                        value: "{test_name}",
+                        oninput: move |e| test_name.set(e.value()),
+                    }
+                    input {
+                        class: "w-32 {INPUT_CLASS}",
+                        placeholder: "Item type",
+                        value: "{test_type}",
+                        oninput: move |e| test_type.set(e.value()),
+                    }
+                }
+                match test_result {
+                    Some(Some((idx, summary))) => rsx! {
+                        div { class: "text-[0.7rem] text-cyan-500", "R{idx + 1} — {summary}" }
+                    },
+                    Some(None) => rsx! {
+                        div { class: "text-[0.7rem] text-zinc-600", "No rule matches - defaults apply" }
+                    },
+                    None => rsx! {},
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use dioxus::prelude::*;
+use std::path::PathBuf;
+use crate::components::edit_items;
+use crate::types::{EditHistory, FormatterItem, SessionJournal};
+
+/// How often pending edits (renames, time changes) are flushed to the journal
+const JOURNAL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Identity and order of every item, used to spot structural changes that get journaled immediately
+fn structure_of(items: &[FormatterItem]) -> Vec<String> {
+    items
+        .iter()
+        .flat_map(|item| match item {
+            FormatterItem::Standalone(entry) => vec![entry.instance_id.clone()],
+            FormatterItem::Group { id, entries, .. } => std::iter::once(id.clone())
+                .chain(entries.iter().map(|e| format!("{}/{}", id, e.instance_id)))
+                .collect(),
+            FormatterItem::Reference { id, .. } => vec![format!("ref:{}", id)],
+            FormatterItem::Delay { id, .. } => vec![format!("delay:{}", id)],
+            FormatterItem::Milestone { id, .. } => vec![format!("milestone:{}", id)],
+        })
+        .collect()
+}
+
+/// Journals the formatter session to the config directory and offers to
+/// restore an unsaved session left behind by a previous run
+#[component]
+pub fn SessionRecovery(
+    formatter_items: Signal<Vec<FormatterItem>>,
+    history: Signal<EditHistory>,
+    playlist_name: Signal<String>,
+    project_path: Signal<Option<PathBuf>>,
+    /// Items as last saved to or opened from `project_path`
+    saved_items: Signal<Vec<FormatterItem>>,
+    logs: Signal<Vec<String>>,
+) -> Element {
+    let mut pending_restore = use_signal(|| Option::<SessionJournal>::None);
+    let mut dirty = use_signal(|| false);
+    let mut last_structure = use_signal(Vec::<String>::new);
+
+    let mut add_log = move |msg: String| {
+        let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
+        logs.write().push(format!("[{}] {}", timestamp, msg));
+    };
+
+    let mut restore = move |journal: SessionJournal| {
+        saved_items.set(if journal.unsaved { vec![] } else { journal.items.clone() });
+        edit_items(formatter_items, history, "Restore session", |items| *items = journal.items);
+        playlist_name.set(journal.playlist_name);
+        project_path.set(journal.project_path.map(PathBuf::from));
+    };
+
+    // Pick up the journal once when the formatter mounts (reads no signals, so it never reruns)
+    use_effect(move || {
+        let Some(journal) = SessionJournal::load() else { return };
+        if journal.items.is_empty() {
+            return;
+        }
+        if journal.is_from_previous_run() {
+            if journal.unsaved {
+                pending_restore.set(Some(journal));
+            }
+        } else {
+            // Same run: the user navigated away and back, so bring the session straight back
+            add_log(format!("Restored formatter session ({} items)", journal.items.len()));
+            restore(journal);
+        }
+    });
+
+    let write_journal = move || {
+        let items = formatter_items.peek().clone();
+        let journal = SessionJournal::new(
+            playlist_name.peek().clone(),
+            project_path.peek().as_ref().map(|p| p.to_string_lossy().to_string()),
+            items != *saved_items.peek(),
+            items,
+        );
+        journal.save()
+    };
+
+    use_effect(move || {
+        let structure = structure_of(&formatter_items.read());
+        // Don't clobber a recoverable session while the restore offer is still open
+        if pending_restore.read().is_some() {
+            return;
+        }
+        if structure != *last_structure.peek() {
+            last_structure.set(structure);
+            if let Err(e) = write_journal() {
+                add_log(format!("✗ Autosave failed: {}", e));
+            }
+            dirty.set(false);
+        } else {
+            dirty.set(true);
+        }
+    });
+
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(JOURNAL_INTERVAL).await;
+            if *dirty.peek() && pending_restore.peek().is_none() {
+                if let Err(e) = write_journal() {
+                    add_log(format!("✗ Autosave failed: {}", e));
+                }
+                dirty.set(false);
+            }
+        }
+    });
+
+    // Leaving the formatter (e.g. for settings) mid-edit flushes what the timer hasn't yet
+    use_drop(move || {
+        let pending = dirty.try_peek().map(|d| *d).unwrap_or(false);
+        let restoring = pending_restore.try_peek().map(|r| r.is_some()).unwrap_or(true);
+        // The formatter's signals may already be gone if it unmounted first
+        if pending && !restoring && formatter_items.try_peek().is_ok() {
+            if let Err(e) = write_journal() {
+                add_log(format!("✗ Autosave failed: {}", e));
+            }
+        }
+    });
+
+    let Some(journal) = pending_restore.read().clone() else {
+        return rsx! {};
+    };
+    let saved_at = chrono::DateTime::parse_from_rfc3339(&journal.saved_at)
+        .map(|t| t.with_timezone(&chrono::Local).format("%a %H:%M").to_string())
+        .unwrap_or_else(|_| journal.saved_at.clone());
+    let source = if journal.playlist_name.is_empty() {
+        "no playlist".to_string()
+    } else {
+        journal.playlist_name.clone()
+    };
+
+    rsx! {
+        div { class: "flex items-center gap-4 shrink-0 bg-amber-500/10 border border-amber-500/30 rounded-lg px-4 py-3 text-xs font-mono",
+            span { class: "text-amber-500 font-bold uppercase tracking-wider", "⚠ Unsaved session" }
+            span { class: "flex-1 text-zinc-300",
+                "{journal.items.len()} items from {source}, autosaved {saved_at}"
+            }
+            button {
+                class: "px-3 py-1.5 rounded bg-amber-500 text-zinc-950 font-bold uppercase tracking-wider hover:bg-amber-400 transition-all",
+                onclick: move |_| {
+                    if let Some(journal) = pending_restore.take() {
+                        add_log(format!("Restored unsaved session ({} items)", journal.items.len()));
+                        restore(journal);
+                    }
+                },
+                "RESTORE"
+            }
+            button {
+                class: "px-3 py-1.5 rounded border border-zinc-700 text-zinc-400 font-bold uppercase tracking-wider hover:text-zinc-200 hover:border-zinc-500 transition-all",
+                onclick: move |_| {
+                    pending_restore.set(None);
+                    if let Err(e) = SessionJournal::clear() {
+                        add_log(format!("✗ Could not discard session: {}", e));
+                    }
+                },
+                "DISCARD"
+            }
+        }
+    }
+}
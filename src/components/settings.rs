@@ -1,5 +1,7 @@
 use dioxus::prelude::*;
+use crate::components::RulesEditor;
 use crate::types::AppSettings;
+use crate::utils::{parse_duration, parse_end_time};
 
 /// Settings page
 #[component]
@@ -8,6 +10,15 @@ pub fn Settings() -> Element {
     let mut settings = use_signal(AppSettings::load);
     let mut save_status = use_signal(|| "");
 
+    let duration_error = parse_duration(&settings.read().default_duration).err();
+    let end_time_error = match settings.read().default_end_time.trim() {
+        // An empty default end time means "no end time"
+        "" => None,
+        s => parse_end_time(s).err(),
+    };
+    let rules_invalid = settings.read().rules.iter().any(|rule| rule.pattern_error().is_some());
+    let has_errors = duration_error.is_some() || end_time_error.is_some() || rules_invalid;
+
     rsx! {
         div { class: "p-8 max-w-5xl mx-auto flex flex-col gap-6 font-mono text-white bg-zinc-950 min-h-screen",
             div { class: "flex justify-between items-end mb-2",
@@ -78,22 +89,30 @@ pub fn Settings() -> Element {
                     div {
                         label { class: "text-[0.65rem] font-bold text-zinc-500 uppercase tracking-wider mb-2 block", "DEFAULT DURATION" }
                         input {
-                            class: "w-full bg-zinc-950 border border-zinc-800 rounded p-3 text-sm font-mono text-zinc-200 focus:outline-none focus:border-emerald-500/50 transition-all placeholder-zinc-700",
+                            class: if duration_error.is_some() { "w-full bg-zinc-950 border border-red-500/50 rounded p-3 text-sm font-mono text-zinc-200 focus:outline-none focus:border-red-500 transition-all placeholder-zinc-700" } else { "w-full bg-zinc-950 border border-zinc-800 rounded p-3 text-sm font-mono text-zinc-200 focus:outline-none focus:border-emerald-500/50 transition-all placeholder-zinc-700" },
                             value: "{settings.read().default_duration}",
                             oninput: move |e| settings.write().default_duration = e.value(),
                         }
+                        if let Some(err) = &duration_error {
+                            div { class: "text-[0.7rem] text-red-500 mt-2", "{err}" }
+                        }
                     }
                     div {
                         label { class: "text-[0.65rem] font-bold text-zinc-500 uppercase tracking-wider mb-2 block", "DEFAULT END TIME" }
                         input {
-                            class: "w-full bg-zinc-950 border border-zinc-800 rounded p-3 text-sm font-mono text-zinc-200 focus:outline-none focus:border-emerald-500/50 transition-all placeholder-zinc-700",
+                            class: if end_time_error.is_some() { "w-full bg-zinc-950 border border-red-500/50 rounded p-3 text-sm font-mono text-zinc-200 focus:outline-none focus:border-red-500 transition-all placeholder-zinc-700" } else { "w-full bg-zinc-950 border border-zinc-800 rounded p-3 text-sm font-mono text-zinc-200 focus:outline-none focus:border-emerald-500/50 transition-all placeholder-zinc-700" },
                             value: "{settings.read().default_end_time}",
                             oninput: move |e| settings.write().default_end_time = e.value(),
                         }
+                        if let Some(err) = &end_time_error {
+                            div { class: "text-[0.7rem] text-red-500 mt-2", "{err}" }
+                        }
                     }
                 }
             }
 
+            RulesEditor { settings }
+
             // Sync Settings
             div { class: "bg-zinc-900 border border-zinc-800 rounded-lg overflow-hidden flex flex-col",
                 div { class: "p-4 flex items-center gap-3 border-b border-zinc-800 bg-zinc-900/50",
@@ -121,6 +140,7 @@ pub fn Settings() -> Element {
 
             button {
                 class: "w-full py-3.5 bg-emerald-500 hover:bg-emerald-400 text-zinc-950 font-extrabold rounded shadow-lg transition-all tracking-wider text-sm mt-4 uppercase",
+                disabled: has_errors,
                 onclick: move |_| {
                     match settings.read().save() {
                         Ok(_) => save_status.set("Configuration Saved!"),
@@ -0,0 +1,239 @@
+use dioxus::prelude::*;
+use crate::components::DurationInput;
+use crate::types::{Duration, ServiceTemplate, TemplateGroup, TemplateSlot};
+
+const INPUT_CLASS: &str = "bg-zinc-950 border border-zinc-800 rounded px-2 py-1.5 text-xs font-mono text-zinc-200 focus:outline-none focus:border-emerald-500/50 transition-all placeholder-zinc-700";
+const SMALL_BUTTON_CLASS: &str = "px-2 py-1 rounded border border-zinc-700 text-zinc-400 text-[0.65rem] font-bold uppercase tracking-wider hover:text-emerald-500 hover:border-emerald-500/50 transition-all";
+const ICON_BUTTON_CLASS: &str = "w-6 h-6 flex items-center justify-center rounded text-zinc-600 hover:text-zinc-200 hover:bg-zinc-800 transition-all disabled:opacity-30";
+const REMOVE_BUTTON_CLASS: &str = "w-6 h-6 flex items-center justify-center rounded text-zinc-600 hover:text-red-500 hover:bg-red-500/10 transition-all";
+
+/// Templates page - create and edit reusable service skeletons
+#[component]
+pub fn Templates() -> Element {
+    let mut templates = use_signal(ServiceTemplate::load_all);
+    let mut selected = use_signal(|| Option::<usize>::None);
+    let mut save_status = use_signal(String::new);
+
+    let selected_idx = selected().filter(|&i| i < templates.read().len());
+    let current = selected_idx.map(|i| templates.read()[i].clone());
+
+    rsx! {
+        div { class: "p-8 max-w-5xl w-full mx-auto flex flex-col gap-6 font-mono text-white bg-zinc-950 h-full overflow-y-auto",
+            div { class: "flex justify-between items-end mb-2",
+                h1 { class: "text-2xl font-extrabold tracking-wider text-zinc-100 uppercase", "SERVICE TEMPLATES" }
+            }
+
+            div { class: "grid grid-cols-[240px_1fr] gap-6 items-start",
+                // Template list
+                div { class: "bg-zinc-900 border border-zinc-800 rounded-lg overflow-hidden flex flex-col",
+                    div { class: "p-4 flex items-center gap-3 border-b border-zinc-800 bg-zinc-900/50",
+                        div { class: "w-2 h-2 rounded-full bg-zinc-600" }
+                        span { class: "text-xs font-bold text-zinc-500 uppercase tracking-widest", "TEMPLATES" }
+                    }
+                    div { class: "p-2 flex flex-col gap-1",
+                        for (idx, template) in templates.read().iter().enumerate() {
+                            button {
+                                key: "{idx}",
+                                class: if selected_idx == Some(idx) { "text-left px-3 py-2 rounded text-sm bg-zinc-800 text-emerald-400" } else { "text-left px-3 py-2 rounded text-sm text-zinc-300 hover:bg-zinc-800" },
+                                onclick: move |_| selected.set(Some(idx)),
+                                "{template.name}"
+                            }
+                        }
+                        if templates.read().is_empty() {
+                            div { class: "px-3 py-2 text-xs text-zinc-600", "No templates yet" }
+                        }
+                    }
+                    button {
+                        class: "m-2 p-2 border-2 border-dashed border-zinc-700 rounded-lg text-zinc-500 text-xs font-bold hover:border-emerald-500/50 hover:text-emerald-500 hover:bg-emerald-500/5 transition-all",
+                        onclick: move |_| {
+                            let count = templates.read().len();
+                            templates.write().push(ServiceTemplate::new(format!("Template {}", count + 1)));
+                            selected.set(Some(count));
+                        },
+                        "+ NEW TEMPLATE"
+                    }
+                }
+
+                // Editor
+                if let (Some(t_idx), Some(template)) = (selected_idx, current) {
+                    div { class: "bg-zinc-900 border border-zinc-800 rounded-lg overflow-hidden flex flex-col",
+                        div { class: "p-4 flex items-center gap-3 border-b border-zinc-800 bg-zinc-900/50",
+                            input {
+                                class: "flex-1 bg-transparent text-sm font-bold text-zinc-100 focus:outline-none",
+                                value: "{template.name}",
+                                oninput: move |e| templates.write()[t_idx].name = e.value(),
+                            }
+                            button {
+                                class: "px-2 py-1 rounded text-[0.65rem] font-bold uppercase tracking-wider text-zinc-500 hover:text-red-500 hover:bg-red-500/10 transition-all",
+                                onclick: move |_| {
+                                    templates.write().remove(t_idx);
+                                    selected.set(None);
+                                },
+                                "DELETE TEMPLATE"
+                            }
+                        }
+                        div { class: "p-4 flex flex-col gap-4",
+                            for (g_idx, group) in template.groups.iter().enumerate() {
+                                div {
+                                    key: "{t_idx}-{g_idx}",
+                                    class: "border border-zinc-800 rounded-lg overflow-hidden",
+                                    style: "border-left: 3px solid {group.color};",
+                                    div { class: "flex items-center gap-2 p-2 bg-zinc-950/40 border-b border-zinc-800",
+                                        input {
+                                            class: "flex-1 {INPUT_CLASS}",
+                                            value: "{group.name}",
+                                            oninput: move |e| templates.write()[t_idx].groups[g_idx].name = e.value(),
+                                        }
+                                        input {
+                                            r#type: "color",
+                                            class: "w-8 h-7 bg-transparent cursor-pointer",
+                                            value: "{group.color}",
+                                            oninput: move |e| templates.write()[t_idx].groups[g_idx].color = e.value(),
+                                        }
+                                        button {
+                                            class: ICON_BUTTON_CLASS,
+                                            title: "Move group up",
+                                            disabled: g_idx == 0,
+                                            onclick: move |_| templates.write()[t_idx].groups.swap(g_idx - 1, g_idx),
+                                            "↑"
+                                        }
+                                        button {
+                                            class: REMOVE_BUTTON_CLASS,
+                                            title: "Remove group",
+                                            onclick: move |_| {
+                                                templates.write()[t_idx].groups.remove(g_idx);
+                                            },
+                                            "×"
+                                        }
+                                    }
+                                    div { class: "p-2 flex flex-col gap-1.5",
+                                        div { class: "grid grid-cols-[1fr_1fr_90px_60px_1fr_52px_40px_40px_24px] gap-2 px-1 text-[0.6rem] font-bold text-zinc-600 uppercase tracking-wider",
+                                            span { "SLOT" }
+                                            span { "MATCH NAME" }
+                                            span { "DURATION" }
+                                            span { "CUE" }
+                                            span { "NOTE" }
+                                            span { title: "Entry colour", "COL" }
+                                            span { title: "Count to end", "CTE" }
+                                            span { title: "Link start", "LINK" }
+                                            span {}
+                                        }
+                                        for (s_idx, slot) in group.slots.iter().enumerate() {
+                                            div {
+                                                key: "{t_idx}-{g_idx}-{s_idx}",
+                                                class: "grid grid-cols-[1fr_1fr_90px_60px_1fr_52px_40px_40px_24px] gap-2 items-center",
+                                                input {
+                                                    class: INPUT_CLASS,
+                                                    value: "{slot.label}",
+                                                    oninput: move |e| templates.write()[t_idx].groups[g_idx].slots[s_idx].label = e.value(),
+                                                }
+                                                input {
+                                                    class: INPUT_CLASS,
+                                                    placeholder: "next item",
+                                                    value: "{slot.match_text}",
+                                                    oninput: move |e| templates.write()[t_idx].groups[g_idx].slots[s_idx].match_text = e.value(),
+                                                }
+                                                DurationInput {
+                                                    value: Some(slot.duration),
+                                                    on_change: move |d: Option<Duration>| {
+                                                        if let Some(d) = d {
+                                                            templates.write()[t_idx].groups[g_idx].slots[s_idx].duration = d;
+                                                        }
+                                                    },
+                                                }
+                                                input {
+                                                    class: INPUT_CLASS,
+                                                    value: "{slot.cue}",
+                                                    oninput: move |e| templates.write()[t_idx].groups[g_idx].slots[s_idx].cue = e.value(),
+                                                }
+                                                input {
+                                                    class: INPUT_CLASS,
+                                                    value: "{slot.note}",
+                                                    oninput: move |e| templates.write()[t_idx].groups[g_idx].slots[s_idx].note = e.value(),
+                                                }
+                                                div { class: "flex items-center gap-0.5",
+                                                    input {
+                                                        r#type: "color",
+                                                        class: "w-6 h-6 bg-transparent cursor-pointer",
+                                                        title: if slot.color.is_empty() { "Colour from rules" } else { "" },
+                                                        value: if slot.color.is_empty() { "#000000".to_string() } else { slot.color.clone() },
+                                                        oninput: move |e| templates.write()[t_idx].groups[g_idx].slots[s_idx].color = e.value(),
+                                                    }
+                                                    if !slot.color.is_empty() {
+                                                        button {
+                                                            class: ICON_BUTTON_CLASS,
+                                                            title: "Use the colour from rules",
+                                                            onclick: move |_| templates.write()[t_idx].groups[g_idx].slots[s_idx].color.clear(),
+                                                            "×"
+                                                        }
+                                                    }
+                                                }
+                                                input {
+                                                    r#type: "checkbox",
+                                                    class: "w-4 h-4 accent-emerald-500 cursor-pointer",
+                                                    checked: slot.count_to_end,
+                                                    onchange: move |e| templates.write()[t_idx].groups[g_idx].slots[s_idx].count_to_end = e.checked(),
+                                                }
+                                                input {
+                                                    r#type: "checkbox",
+                                                    class: "w-4 h-4 accent-emerald-500 cursor-pointer",
+                                                    checked: slot.link_start,
+                                                    onchange: move |e| templates.write()[t_idx].groups[g_idx].slots[s_idx].link_start = e.checked(),
+                                                }
+                                                button {
+                                                    class: REMOVE_BUTTON_CLASS,
+                                                    onclick: move |_| {
+                                                        templates.write()[t_idx].groups[g_idx].slots.remove(s_idx);
+                                                    },
+                                                    "×"
+                                                }
+                                            }
+                                        }
+                                        button {
+                                            class: "self-start {SMALL_BUTTON_CLASS}",
+                                            onclick: move |_| {
+                                                let mut list = templates.write();
+                                                let slots = &mut list[t_idx].groups[g_idx].slots;
+                                                slots.push(TemplateSlot::new(format!("Slot {}", slots.len() + 1)));
+                                            },
+                                            "+ SLOT"
+                                        }
+                                    }
+                                }
+                            }
+                            button {
+                                class: "p-2 border-2 border-dashed border-zinc-700 rounded-lg text-zinc-500 text-xs font-bold hover:border-emerald-500/50 hover:text-emerald-500 hover:bg-emerald-500/5 transition-all",
+                                onclick: move |_| {
+                                    let mut list = templates.write();
+                                    let groups = &mut list[t_idx].groups;
+                                    groups.push(TemplateGroup::new(format!("GROUP {}", groups.len() + 1)));
+                                },
+                                "+ GROUP"
+                            }
+                        }
+                    }
+                } else {
+                    div { class: "bg-zinc-900/50 border border-dashed border-zinc-800 rounded-lg p-8 text-center text-xs text-zinc-600",
+                        "Select or create a template. Apply it from the Formatter once a playlist is loaded."
+                    }
+                }
+            }
+
+            button {
+                class: "w-full py-3.5 bg-emerald-500 hover:bg-emerald-400 text-zinc-950 font-extrabold rounded shadow-lg transition-all tracking-wider text-sm uppercase",
+                onclick: move |_| {
+                    match ServiceTemplate::save_all(&templates.read()) {
+                        Ok(_) => save_status.set("Templates Saved!".to_string()),
+                        Err(e) => save_status.set(format!("Failed to save: {}", e)),
+                    }
+                },
+                "SAVE TEMPLATES"
+            }
+            if !save_status.read().is_empty() {
+                div { class: "text-center text-emerald-500 font-bold font-mono text-xs animate-fade-in",
+                    "{save_status}"
+                }
+            }
+        }
+    }
+}
@@ -1,34 +1,72 @@
 use dioxus::prelude::*;
-use crate::types::{AppSettings, TimeField};
+use crate::types::{AppSettings, Duration, TimeField, TimeOfDay, MS_PER_DAY};
+use crate::components::keep_text_undo;
+use crate::utils::{parse_time_expr, TimeExprContext};
 
+/// Split milliseconds into (days, hours, minutes, seconds) for the spinner inputs.
+/// Durations keep every hour in the hour column; end times carry whole days separately.
+fn split_time(ms: u64, field: TimeField) -> (i32, i32, i32, i32) {
+    let (days, rest) = match field {
+        TimeField::Duration => (0, ms),
+        TimeField::EndTime => (ms / MS_PER_DAY, ms % MS_PER_DAY),
+    };
+    let total = (rest / 1000) as i32;
+    (days as i32, total / 3600, (total % 3600) / 60, total % 60)
+}
+
+/// Value in ms: a duration, or an end time since midnight of the first day
 #[component]
 pub fn TimePicker(
-    value: String,
+    value: u64,
     field: TimeField,
+    /// End time of the active reference item, enables `ref+10m` expressions
+    reference: Option<u64>,
     on_close: EventHandler<()>,
-    on_save: EventHandler<String>,
+    on_save: EventHandler<u64>,
 ) -> Element {
     let mut settings = use_signal(AppSettings::load);
-    // Parse initial value "HH:MM:SS"
-    let (initial_h, initial_m, initial_s) = {
-        let parts: Vec<&str> = value.split(':').collect();
-        let h = parts.get(0).and_then(|s| s.parse::<i32>().ok()).unwrap_or(0);
-        let m = parts.get(1).and_then(|s| s.parse::<i32>().ok()).unwrap_or(0);
-        let s = parts.get(2).and_then(|s| s.parse::<i32>().ok()).unwrap_or(0);
-        (h, m, s)
-    };
+    let (initial_d, initial_h, initial_m, initial_s) = split_time(value, field);
 
+    let mut d = use_signal(|| initial_d);
     let mut h = use_signal(|| initial_h);
     let mut m = use_signal(|| initial_m);
     let mut s = use_signal(|| initial_s);
+    let mut expr = use_signal(String::new);
+    let mut expr_error = use_signal(|| Option::<String>::None);
+
+    let current_ms = move || {
+        let secs = (d() as i64 * 86_400 + h() as i64 * 3600 + m() as i64 * 60 + s() as i64).max(0);
+        secs as u64 * 1000
+    };
+
+    // Resolve a typed expression against the spinner value and the reference item
+    let mut apply_expr = move |text: String| {
+        match parse_time_expr(&text, field, TimeExprContext { current: current_ms(), reference }) {
+            Ok(ms) => {
+                let (nd, nh, nm, ns) = split_time(ms, field);
+                d.set(nd);
+                h.set(nh);
+                m.set(nm);
+                s.set(ns);
+                expr_error.set(None);
+                true
+            }
+            Err(e) => {
+                expr_error.set(Some(e.to_string()));
+                false
+            }
+        }
+    };
 
     let save = move |_| {
-        let new_time = format!("{:02}:{:02}:{:02}", h(), m(), s());
-        on_save.call(new_time);
+        on_save.call(current_ms());
     };
 
     let add_favorite = move |_| {
-        let new_time = format!("{:02}:{:02}:{:02}", h(), m(), s());
+        let new_time = match field {
+            TimeField::Duration => Duration::from_ms(current_ms()).to_string(),
+            TimeField::EndTime => TimeOfDay::from_schedule_ms(current_ms()).to_string(),
+        };
         let mut current_settings = settings.write();
         match field {
             TimeField::Duration => {
@@ -47,10 +85,14 @@ pub fn TimePicker(
     };
     
     // Validation Helpers
-    let is_valid_h = h() >= 0 && h() <= 23;
+    let is_valid_d = d() >= 0;
+    let is_valid_h = match field {
+        TimeField::Duration => h() >= 0,
+        TimeField::EndTime => h() >= 0 && h() <= 23,
+    };
     let is_valid_m = m() >= 0 && m() <= 59;
     let is_valid_s = s() >= 0 && s() <= 59;
-    let is_form_valid = is_valid_h && is_valid_m && is_valid_s;
+    let is_form_valid = is_valid_d && is_valid_h && is_valid_m && is_valid_s;
 
     let favorites = match field {
         TimeField::Duration => settings.read().favorite_durations.clone(),
@@ -68,15 +110,60 @@ pub fn TimePicker(
                         span { "Set Duration" }
                     }
                 }
+                div { class: "tp-expr",
+                    input {
+                        r#type: "text",
+                        class: if expr_error.read().is_some() { "tp-expr-input invalid" } else { "tp-expr-input" },
+                        placeholder: if reference.is_some() { "1h30m, 4:30pm, +5m, ref+10m" } else { "1h30m, 4:30pm, +5m" },
+                        value: "{expr}",
+                        oninput: move |e| {
+                            let text = e.value();
+                            expr.set(text.clone());
+                            if text.trim().is_empty() {
+                                expr_error.set(None);
+                            }
+                        },
+                        onkeydown: move |e: Event<KeyboardData>| {
+                            // Keep Ctrl+Z for the field, not the plan
+                            e.stop_propagation();
+                            if e.key() == Key::Enter && apply_expr(expr()) {
+                                expr.set(String::new());
+                            }
+                        },
+                        onblur: move |_| {
+                            if !expr().trim().is_empty() && apply_expr(expr()) {
+                                expr.set(String::new());
+                            }
+                        },
+                    }
+                    if let Some(err) = expr_error.read().clone() {
+                        div { class: "tp-error-msg", "{err}" }
+                    }
+                }
                 div { class: "tp-inputs",
+                    if matches!(field, TimeField::EndTime) {
+                        div { class: "tp-column",
+                            label { "+DAY" }
+                            input {
+                                r#type: "number",
+                                min: "0",
+                                class: if is_valid_d { "tp-input" } else { "tp-input invalid" },
+                                value: "{d}",
+                                onkeydown: keep_text_undo,
+                                oninput: move |e| d.set(e.value().parse().unwrap_or(0))
+                            }
+                        }
+                        span { class: "tp-separator", " " }
+                    }
                     div { class: "tp-column",
                         label { "HR" }
                         input {
                             r#type: "number",
                             min: "0",
-                            max: "23",
+                            max: if matches!(field, TimeField::EndTime) { "23" } else { "" },
                             class: if is_valid_h { "tp-input" } else { "tp-input invalid" },
                             value: "{h}",
+                            onkeydown: keep_text_undo,
                             oninput: move |e| h.set(e.value().parse().unwrap_or(0))
                         }
                     }
@@ -89,6 +176,7 @@ pub fn TimePicker(
                             max: "59",
                             class: if is_valid_m { "tp-input" } else { "tp-input invalid" },
                             value: "{m}",
+                            onkeydown: keep_text_undo,
                             oninput: move |e| m.set(e.value().parse().unwrap_or(0))
                         }
                     }
@@ -101,6 +189,7 @@ pub fn TimePicker(
                             max: "59",
                             class: if is_valid_s { "tp-input" } else { "tp-input invalid" },
                             value: "{s}",
+                            onkeydown: keep_text_undo,
                             oninput: move |e| s.set(e.value().parse().unwrap_or(0))
                         }
                     }
@@ -108,7 +197,11 @@ pub fn TimePicker(
                 
                 if !is_form_valid {
                     div { class: "tp-error-msg",
-                        "Invalid Time Format. Please ensure 0-23 hours and 0-59 minutes/seconds."
+                        if matches!(field, TimeField::EndTime) {
+                            "Invalid Time Format. Please ensure 0-23 hours and 0-59 minutes/seconds."
+                        } else {
+                            "Invalid Time Format. Please ensure 0-59 minutes/seconds."
+                        }
                     }
                 }
                 
@@ -125,12 +218,7 @@ pub fn TimePicker(
                                             button {
                                                 class: "tp-fav-chip",
                                                 onclick: move |_| {
-                                                    let parts: Vec<&str> = fav_click.split(':').collect();
-                                                    if parts.len() == 3 {
-                                                        h.set(parts[0].parse().unwrap_or(0));
-                                                        m.set(parts[1].parse().unwrap_or(0));
-                                                        s.set(parts[2].parse().unwrap_or(0));
-                                                    }
+                                                    apply_expr(fav_click.clone());
                                                 },
                                                 "{fav_display}"
                                             }
@@ -4,7 +4,7 @@ mod types;
 mod utils;
 mod components;
 
-use components::{Navbar, Settings, Formatter};
+use components::{Navbar, Settings, Formatter, Templates, Reports};
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
 const MAIN_CSS: Asset = asset!("/assets/main.css");
@@ -16,6 +16,10 @@ pub enum Route {
     #[layout(Navbar)]
     #[route("/settings")]
     Settings {},
+    #[route("/templates")]
+    Templates {},
+    #[route("/reports")]
+    Reports {},
     #[route("/")]
     Formatter {},
 }
@@ -0,0 +1,194 @@
+use rust_xlsxwriter::{Format, Workbook};
+use super::formatter::{FormatterItem, TimedEntry};
+use super::time::{Duration, TimeOfDay};
+use crate::utils::format_ms_to_duration;
+
+/// What a schedule row comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleRowKind {
+    Event,
+    Delay,
+    Milestone,
+}
+
+impl ScheduleRowKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ScheduleRowKind::Event => "event",
+            ScheduleRowKind::Delay => "delay",
+            ScheduleRowKind::Milestone => "milestone",
+        }
+    }
+}
+
+/// One line of the plan with its start and end worked out, groups flattened
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleRow {
+    pub kind: ScheduleRowKind,
+    /// Name and colour of the group the row sits in
+    pub group: Option<(String, String)>,
+    pub title: String,
+    pub cue: String,
+    pub note: String,
+    pub color: String,
+    /// None until something earlier in the plan pins the time down
+    pub start: Option<TimeOfDay>,
+    pub end: Option<TimeOfDay>,
+    pub duration: Duration,
+    pub count_to_end: bool,
+    pub link_start: bool,
+}
+
+impl ScheduleRow {
+    fn from_entry(entry: &TimedEntry, group: Option<(String, String)>, previous_end: Option<TimeOfDay>) -> Self {
+        // An entry's own end time wins; otherwise it follows straight on from the previous one
+        let (start, end) = if !entry.end_time.is_midnight() {
+            let start = TimeOfDay::from_schedule_ms(entry.end_time.schedule_ms().saturating_sub(entry.duration.as_ms()));
+            (Some(start), Some(entry.end_time))
+        } else {
+            (previous_end, previous_end.map(|start| start.after(entry.duration)))
+        };
+        Self {
+            kind: ScheduleRowKind::Event,
+            group,
+            title: entry.name.clone(),
+            cue: entry.cue.clone(),
+            note: entry.note.clone(),
+            color: entry.color.clone(),
+            start,
+            end,
+            duration: entry.duration,
+            count_to_end: entry.count_to_end,
+            link_start: entry.link_start,
+        }
+    }
+}
+
+/// The plan as a flat list of rows with computed start and end times.
+/// References anchor the times that follow them but aren't rows themselves.
+pub fn computed_schedule(items: &[FormatterItem]) -> Vec<ScheduleRow> {
+    let mut rows = Vec::new();
+    let mut previous_end: Option<TimeOfDay> = None;
+    for item in items {
+        match item {
+            FormatterItem::Standalone(entry) => {
+                let row = ScheduleRow::from_entry(entry, None, previous_end);
+                previous_end = row.end.or(previous_end);
+                rows.push(row);
+            }
+            FormatterItem::Group { name, color, entries, .. } => {
+                for entry in entries {
+                    let row = ScheduleRow::from_entry(entry, Some((name.clone(), color.clone())), previous_end);
+                    previous_end = row.end.or(previous_end);
+                    rows.push(row);
+                }
+            }
+            FormatterItem::Reference { time_end, .. } => {
+                if !time_end.is_midnight() {
+                    previous_end = Some(*time_end);
+                }
+            }
+            FormatterItem::Delay { duration, .. } => {
+                let end = previous_end.map(|start| start.after(*duration));
+                rows.push(ScheduleRow {
+                    kind: ScheduleRowKind::Delay,
+                    group: None,
+                    title: "Delay".to_string(),
+                    cue: String::new(),
+                    note: String::new(),
+                    color: String::new(),
+                    start: previous_end,
+                    end,
+                    duration: *duration,
+                    count_to_end: false,
+                    link_start: false,
+                });
+                previous_end = end.or(previous_end);
+            }
+            FormatterItem::Milestone { title, cue, note, color, .. } => rows.push(ScheduleRow {
+                kind: ScheduleRowKind::Milestone,
+                group: None,
+                title: title.clone(),
+                cue: cue.clone(),
+                note: note.clone(),
+                color: color.clone(),
+                start: previous_end,
+                end: previous_end,
+                duration: Duration::default(),
+                count_to_end: false,
+                link_start: false,
+            }),
+        }
+    }
+    rows
+}
+
+fn optional_time(time: Option<TimeOfDay>) -> String {
+    time.map(|t| t.to_string()).unwrap_or_default()
+}
+
+/// The plan as CSV, one row per entry, delay and milestone
+pub fn schedule_to_csv(rows: &[ScheduleRow]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer
+        .write_record([
+            "Group", "Type", "Cue", "Title", "Start", "End", "Duration", "Count to end", "Link start", "Colour", "Note",
+        ])
+        .map_err(|e| e.to_string())?;
+    for row in rows {
+        writer
+            .write_record([
+                row.group.as_ref().map(|(name, _)| name.clone()).unwrap_or_default(),
+                row.kind.label().to_string(),
+                row.cue.clone(),
+                row.title.clone(),
+                optional_time(row.start),
+                optional_time(row.end),
+                row.duration.to_string(),
+                row.count_to_end.to_string(),
+                row.link_start.to_string(),
+                row.color.clone(),
+                row.note.clone(),
+            ])
+            .map_err(|e| e.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+/// The plan as an xlsx laid out the way Ontime's spreadsheet import expects by
+/// default: an "event schedule" worksheet with one event per row. Ontime's
+/// import only creates events, so delays and milestones are left out.
+pub fn schedule_to_xlsx(rows: &[ScheduleRow]) -> Result<Vec<u8>, String> {
+    const HEADERS: [&str; 10] = [
+        "Time Start", "Time End", "Duration", "Cue", "Title", "Count to End", "Link Start", "Colour", "Notes", "Group",
+    ];
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("event schedule").map_err(|e| e.to_string())?;
+    let bold = Format::new().set_bold();
+    for (col, header) in HEADERS.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *header, &bold).map_err(|e| e.to_string())?;
+    }
+    let time_of_day = |time: Option<TimeOfDay>| time.map(|t| format_ms_to_duration(t.ms_of_day())).unwrap_or_default();
+    let flag = |on: bool| if on { "x" } else { "" };
+    for (idx, row) in rows.iter().filter(|r| r.kind == ScheduleRowKind::Event).enumerate() {
+        let cells = [
+            time_of_day(row.start),
+            time_of_day(row.end),
+            row.duration.to_string(),
+            row.cue.clone(),
+            row.title.clone(),
+            flag(row.count_to_end).to_string(),
+            flag(row.link_start).to_string(),
+            row.color.clone(),
+            row.note.clone(),
+            row.group.as_ref().map(|(name, _)| name.clone()).unwrap_or_default(),
+        ];
+        for (col, cell) in cells.iter().enumerate() {
+            sheet.write_string(idx as u32 + 1, col as u16, cell).map_err(|e| e.to_string())?;
+        }
+    }
+    sheet.autofit();
+    workbook.save_to_buffer().map_err(|e| e.to_string())
+}
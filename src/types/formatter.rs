@@ -1,17 +1,112 @@
+use serde::{Deserialize, Serialize};
+use super::propresenter::PlaylistItem;
+use super::time::{Duration, TimeOfDay};
+
 /// Timed entry for formatter - represents an item with timing info
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimedEntry {
+    /// Identity of this entry in the plan. The same ProPresenter item can be planned
+    /// more than once, so `item_id` alone doesn't tell instances apart.
+    #[serde(default = "TimedEntry::new_instance_id")]
+    pub instance_id: String,
     pub item_id: String,
+    /// ProPresenter presentation behind the item, which timing history is keyed by
+    #[serde(default)]
+    pub presentation_uuid: Option<String>,
     pub name: String,
     pub item_type: String,
-    pub duration: String,
-    pub end_time: String,
+    pub duration: Duration,
+    pub end_time: TimeOfDay,
     pub count_to_end: bool,
     pub link_start: bool,
+    /// Event colour pushed to Ontime (empty = none)
+    #[serde(default)]
+    pub color: String,
+    #[serde(default)]
+    pub cue: String,
+    #[serde(default)]
+    pub note: String,
+    /// Created in the bridge rather than from a playlist item. Nothing in
+    /// ProPresenter backs it, so `item_id` is empty and the title is editable.
+    #[serde(default)]
+    pub manual: bool,
+    /// Fixed end time (sermon end, service end) that ripple edits stop at
+    #[serde(default)]
+    pub locked: bool,
+    /// Duration that fit-to-time leaves alone
+    #[serde(default)]
+    pub fixed: bool,
+    /// Id Ontime gave this instance when it was last pushed. A later push skips
+    /// the entry while that event is still in the rundown.
+    #[serde(default)]
+    pub ontime_id: Option<String>,
     /// Optional insertion index for UI placement (0 = start, n = after n-th existing entry)
     pub insertion_index: Option<usize>,
 }
 
+impl TimedEntry {
+    /// New entry for a playlist item, before any rules are applied
+    pub fn from_playlist_item(item: &PlaylistItem, duration: Duration, end_time: TimeOfDay) -> Self {
+        Self {
+            instance_id: Self::new_instance_id(),
+            item_id: item.id.uuid.clone(),
+            presentation_uuid: item.presentation_uuid().map(str::to_string),
+            name: item.id.name.clone(),
+            item_type: item.item_type.clone(),
+            duration,
+            end_time,
+            count_to_end: false,
+            link_start: true,
+            color: String::new(),
+            cue: String::new(),
+            note: String::new(),
+            manual: false,
+            locked: false,
+            fixed: false,
+            ontime_id: None,
+            insertion_index: None,
+        }
+    }
+
+    /// Bridge-owned entry with no ProPresenter item behind it
+    pub fn manual(title: String, duration: Duration, end_time: TimeOfDay) -> Self {
+        Self {
+            instance_id: Self::new_instance_id(),
+            item_id: String::new(),
+            presentation_uuid: None,
+            name: title,
+            item_type: "manual".to_string(),
+            duration,
+            end_time,
+            count_to_end: false,
+            link_start: true,
+            color: String::new(),
+            cue: String::new(),
+            note: String::new(),
+            manual: true,
+            locked: false,
+            fixed: false,
+            ontime_id: None,
+            insertion_index: None,
+        }
+    }
+
+    pub fn new_instance_id() -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+}
+
+/// How many times each ProPresenter item is planned, for duplicate warnings
+pub fn item_use_counts(items: &[FormatterItem]) -> std::collections::HashMap<String, usize> {
+    let mut counts = std::collections::HashMap::new();
+    for path in EntryPath::all(items) {
+        if let Some(entry) = path.entry(items).filter(|e| !e.manual) {
+            *counts.entry(entry.item_id.clone()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
 pub struct FormatterGroup {
@@ -22,10 +117,51 @@ pub struct FormatterGroup {
     pub collapsed: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Where pushed items land relative to the entry a reference points at
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum InsertionMode {
+    /// Just before the entry, at its level
+    Before,
+    /// After the entry at the top level (after its group, if it's in one)
     After,
-    Into, // For groups
+    /// First thing inside the group. Plans saved before the other modes existed say `Into`.
+    #[serde(alias = "Into")]
+    IntoStart,
+    /// Last thing inside the group
+    IntoEnd,
+    /// After the entry, inside the same group
+    AfterInGroup,
+}
+
+impl InsertionMode {
+    /// Modes that make sense for an entry of this Ontime type
+    pub fn valid_for(item_type: &str) -> &'static [InsertionMode] {
+        if item_type == "group" {
+            &[InsertionMode::Before, InsertionMode::After, InsertionMode::IntoStart, InsertionMode::IntoEnd]
+        } else {
+            &[InsertionMode::Before, InsertionMode::After, InsertionMode::AfterInGroup]
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InsertionMode::Before => "INSERT BEFORE",
+            InsertionMode::After => "INSERT AFTER",
+            InsertionMode::IntoStart => "INTO START",
+            InsertionMode::IntoEnd => "INTO END",
+            InsertionMode::AfterInGroup => "AFTER IN GROUP",
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            InsertionMode::Before => "↑",
+            InsertionMode::After => "↓",
+            InsertionMode::IntoStart => "↳",
+            InsertionMode::IntoEnd => "⤵",
+            InsertionMode::AfterInGroup => "↘",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Copy)]
@@ -34,16 +170,403 @@ pub enum TimeField {
     EndTime,
 }
 
+/// Position of a timed entry in the plan: a standalone item, or an entry inside a group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntryPath {
+    pub item_idx: usize,
+    pub sub_idx: Option<usize>,
+}
+
+impl EntryPath {
+    pub fn standalone(item_idx: usize) -> Self {
+        Self { item_idx, sub_idx: None }
+    }
+
+    pub fn in_group(item_idx: usize, sub_idx: usize) -> Self {
+        Self { item_idx, sub_idx: Some(sub_idx) }
+    }
+
+    /// Every entry in the plan, in display order
+    pub fn all(items: &[FormatterItem]) -> Vec<EntryPath> {
+        items
+            .iter()
+            .enumerate()
+            .flat_map(|(item_idx, item)| match item {
+                FormatterItem::Standalone(_) => vec![EntryPath::standalone(item_idx)],
+                FormatterItem::Group { entries, .. } => {
+                    (0..entries.len()).map(|sub_idx| EntryPath::in_group(item_idx, sub_idx)).collect()
+                }
+                FormatterItem::Reference { .. } | FormatterItem::Delay { .. } | FormatterItem::Milestone { .. } => vec![],
+            })
+            .collect()
+    }
+
+    pub fn entry(self, items: &[FormatterItem]) -> Option<&TimedEntry> {
+        match (items.get(self.item_idx)?, self.sub_idx) {
+            (FormatterItem::Standalone(entry), None) => Some(entry),
+            (FormatterItem::Group { entries, .. }, Some(sub_idx)) => entries.get(sub_idx),
+            _ => None,
+        }
+    }
+
+    pub fn entry_mut(self, items: &mut [FormatterItem]) -> Option<&mut TimedEntry> {
+        match (items.get_mut(self.item_idx)?, self.sub_idx) {
+            (FormatterItem::Standalone(entry), None) => Some(entry),
+            (FormatterItem::Group { entries, .. }, Some(sub_idx)) => entries.get_mut(sub_idx),
+            _ => None,
+        }
+    }
+}
+
+/// Remove the entries at `paths` and return them in plan order. Standalone
+/// items are removed outright; a group goes too once its last entry is taken.
+pub fn take_entries(items: &mut Vec<FormatterItem>, paths: &[EntryPath]) -> Vec<TimedEntry> {
+    let mut paths = paths.to_vec();
+    paths.sort();
+    paths.dedup();
+    // Remove back to front so earlier paths stay valid
+    let mut taken: Vec<TimedEntry> = paths
+        .iter()
+        .rev()
+        .filter_map(|path| match (items.get_mut(path.item_idx)?, path.sub_idx) {
+            (FormatterItem::Standalone(_), None) => match items.remove(path.item_idx) {
+                FormatterItem::Standalone(entry) => Some(entry),
+                _ => None,
+            },
+            (FormatterItem::Group { entries, .. }, Some(sub_idx)) if sub_idx < entries.len() => {
+                let entry = entries.remove(sub_idx);
+                if entries.is_empty() {
+                    items.remove(path.item_idx);
+                }
+                Some(entry)
+            }
+            _ => None,
+        })
+        .collect();
+    taken.reverse();
+    taken
+}
+
+/// Top-level indices `take_entries(items, paths)` removes: standalone entries,
+/// and groups that lose every entry
+fn removed_by_take(items: &[FormatterItem], paths: &[EntryPath]) -> Vec<usize> {
+    items
+        .iter()
+        .enumerate()
+        .filter(|(item_idx, item)| match item {
+            FormatterItem::Standalone(_) => paths.contains(&EntryPath::standalone(*item_idx)),
+            FormatterItem::Group { entries, .. } => {
+                !entries.is_empty()
+                    && (0..entries.len()).all(|sub_idx| paths.contains(&EntryPath::in_group(*item_idx, sub_idx)))
+            }
+            _ => false,
+        })
+        .map(|(item_idx, _)| item_idx)
+        .collect()
+}
+
+/// Where the top-level position `idx` ends up once `take_entries(items, paths)` has run
+pub fn index_after_take(items: &[FormatterItem], paths: &[EntryPath], idx: usize) -> usize {
+    idx - removed_by_take(items, paths).iter().filter(|removed| **removed < idx).count()
+}
+
+/// Move the entries at `paths` to the end of the group at `group_idx`, in plan
+/// order. Entries already in that group stay where they are.
+pub fn move_entries_to_group(items: &mut Vec<FormatterItem>, paths: &[EntryPath], group_idx: usize) {
+    if !matches!(items.get(group_idx), Some(FormatterItem::Group { .. })) {
+        return;
+    }
+    let paths: Vec<EntryPath> = paths.iter().copied().filter(|p| p.item_idx != group_idx).collect();
+    let group_idx = index_after_take(items, &paths, group_idx);
+    let moved = take_entries(items, &paths);
+    if let Some(FormatterItem::Group { entries, .. }) = items.get_mut(group_idx) {
+        entries.extend(moved);
+    }
+}
+
+/// What is being dragged onto the formatter
+#[derive(Debug, Clone, PartialEq)]
+pub enum DragSource {
+    /// A top-level formatter item, by index
+    Item(usize),
+    /// An entry inside a group
+    Entry(EntryPath),
+    /// An item from the ProPresenter playlist list
+    Playlist(PlaylistItem),
+}
+
+/// Where a dragged item would land
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DropTarget {
+    /// Onto a top-level item, taking its place when reordering
+    Item(usize),
+    /// Between top-level items, before the given index (the item count means the end)
+    Gap(usize),
+    /// Into a group, before the entry at the given index
+    GroupEntry(usize, usize),
+    /// Into a group, after its last entry
+    GroupEnd(usize),
+}
+
+/// Move a timed entry, standalone or grouped, to a drop target: into a group
+/// at a position, or out to the top level as a standalone entry
+pub fn move_entry(items: &mut Vec<FormatterItem>, from: EntryPath, target: DropTarget) {
+    let into_group = match target {
+        DropTarget::GroupEntry(group_idx, sub_idx) => Some((group_idx, Some(sub_idx))),
+        DropTarget::GroupEnd(group_idx) => Some((group_idx, None)),
+        DropTarget::Item(_) | DropTarget::Gap(_) => None,
+    };
+    if let Some((group_idx, _)) = into_group {
+        match items.get(group_idx) {
+            // Moving a group's only entry within it changes nothing
+            Some(FormatterItem::Group { entries, .. }) if from.item_idx == group_idx && entries.len() == 1 => return,
+            Some(FormatterItem::Group { .. }) => {}
+            _ => return,
+        }
+    }
+    // Taking the entry out removes a standalone item, or a group left empty, shifting later indices up
+    let removed = removed_by_take(items, &[from]);
+    let shift = |idx: usize| idx - removed.iter().filter(|r| **r < idx).count();
+    let Some(entry) = take_entries(items, &[from]).pop() else { return };
+
+    match into_group {
+        Some((group_idx, sub_idx)) => {
+            let same_group = from.item_idx == group_idx;
+            let group_idx = shift(group_idx);
+            if let FormatterItem::Group { entries, .. } = &mut items[group_idx] {
+                let at = match (sub_idx, from.sub_idx) {
+                    (Some(at), Some(from_sub)) if same_group && from_sub < at => at - 1,
+                    (Some(at), _) => at,
+                    (None, _) => entries.len(),
+                };
+                entries.insert(at.min(entries.len()), entry);
+            }
+        }
+        None => {
+            let at = match target {
+                DropTarget::Item(idx) | DropTarget::Gap(idx) => shift(idx),
+                _ => items.len(),
+            };
+            items.insert(at.min(items.len()), FormatterItem::Standalone(entry));
+        }
+    }
+}
+
+/// Move the end times of the entries after `from` by `delta_ms`, stopping at the
+/// first entry with a locked end time. Entries without an end time are left alone.
+pub fn ripple_end_times(items: &mut [FormatterItem], from: EntryPath, delta_ms: i64) {
+    if delta_ms == 0 {
+        return;
+    }
+    let order = EntryPath::all(items);
+    let Some(pos) = order.iter().position(|p| *p == from) else { return };
+    for path in &order[pos + 1..] {
+        let Some(entry) = path.entry_mut(items) else { continue };
+        if entry.locked {
+            break;
+        }
+        if !entry.end_time.is_midnight() {
+            entry.end_time = entry.end_time.shifted(delta_ms);
+        }
+    }
+}
+
+/// Ripple a duration or end-time edit to the entry at `path` through the plan.
+/// `old_end` and `old_duration` are the entry's values before the edit. A longer
+/// entry ends later, unless its end is the locked one; entries without an end time don't ripple.
+pub fn ripple_edit(items: &mut [FormatterItem], path: EntryPath, old_end: TimeOfDay, old_duration: Duration, field: TimeField) {
+    let delta = match path.entry_mut(items) {
+        Some(entry) if !old_end.is_midnight() => match field {
+            TimeField::Duration if !entry.locked => {
+                let delta = entry.duration.as_ms() as i64 - old_duration.as_ms() as i64;
+                entry.end_time = entry.end_time.shifted(delta);
+                delta
+            }
+            TimeField::Duration => 0,
+            TimeField::EndTime => entry.end_time.schedule_ms() as i64 - old_end.schedule_ms() as i64,
+        },
+        _ => 0,
+    };
+    ripple_end_times(items, path, delta);
+}
+
+/// Locked entries that can't start on time, keyed by instance id, with how far
+/// what comes before them runs into their slot. Entries without an end time follow
+/// on from the one before, as in `computed_schedule`, so their durations count too.
+pub fn anchor_conflicts(items: &[FormatterItem]) -> std::collections::HashMap<String, Duration> {
+    let mut conflicts = std::collections::HashMap::new();
+    let mut previous_end: Option<TimeOfDay> = None;
+    let mut visit = |entry: &TimedEntry, previous_end: &mut Option<TimeOfDay>| {
+        if entry.end_time.is_midnight() {
+            *previous_end = previous_end.map(|end| end.after(entry.duration));
+            return;
+        }
+        let start = entry.end_time.before(entry.duration);
+        if let Some(previous) = previous_end.filter(|previous| entry.locked && *previous > start) {
+            conflicts.insert(
+                entry.instance_id.clone(),
+                Duration::from_ms(previous.schedule_ms() - start.schedule_ms()),
+            );
+        }
+        *previous_end = Some(entry.end_time);
+    };
+    for item in items {
+        match item {
+            FormatterItem::Standalone(entry) => visit(entry, &mut previous_end),
+            FormatterItem::Group { entries, .. } => {
+                for entry in entries {
+                    visit(entry, &mut previous_end);
+                }
+            }
+            FormatterItem::Reference { time_end, .. } => {
+                if !time_end.is_midnight() {
+                    previous_end = Some(*time_end);
+                }
+            }
+            FormatterItem::Delay { duration, .. } => previous_end = previous_end.map(|end| end.after(*duration)),
+            FormatterItem::Milestone { .. } => {}
+        }
+    }
+    conflicts
+}
+
+/// Durations that make entries add up to `target`. Fixed entries (`true`) keep
+/// theirs; the rest scale proportionally, rounded to whole seconds, with the
+/// last flexible entry taking up the rounding.
+pub fn fit_durations(entries: &[(Duration, bool)], target: Duration) -> Result<Vec<Duration>, String> {
+    let fixed_total: u64 = entries.iter().filter(|(_, fixed)| *fixed).map(|(d, _)| d.as_ms()).sum();
+    let flexible_total: u64 = entries.iter().filter(|(_, fixed)| !*fixed).map(|(d, _)| d.as_ms()).sum();
+    if fixed_total > target.as_ms() {
+        return Err(format!(
+            "Fixed entries alone take {}, more than {}",
+            Duration::from_ms(fixed_total),
+            target
+        ));
+    }
+    if flexible_total == 0 {
+        return Err("No flexible entries with a duration to scale".to_string());
+    }
+    let available = target.as_ms() - fixed_total;
+    let last_flexible = entries.iter().rposition(|(d, fixed)| !*fixed && d.as_ms() > 0);
+    let mut assigned = 0;
+    let mut fitted = Vec::with_capacity(entries.len());
+    for (idx, (duration, fixed)) in entries.iter().enumerate() {
+        if *fixed || duration.as_ms() == 0 {
+            fitted.push(*duration);
+            continue;
+        }
+        let ms = if Some(idx) == last_flexible {
+            // Rounding the earlier entries up can use more than there is
+            available.checked_sub(assigned).filter(|ms| *ms > 0).ok_or_else(|| {
+                format!("{} is too tight to share out in whole seconds", Duration::from_ms(available))
+            })?
+        } else {
+            let scaled = duration.as_ms() as u128 * available as u128 / flexible_total as u128;
+            (scaled as u64 + 500) / 1000 * 1000
+        };
+        assigned += ms;
+        fitted.push(Duration::from_ms(ms));
+    }
+    Ok(fitted)
+}
+
+/// First `group-N` id not already used in the plan
+pub fn next_group_id(items: &[FormatterItem]) -> String {
+    (1..)
+        .map(|n| format!("group-{}", n))
+        .find(|candidate| {
+            !items.iter().any(|item| matches!(item, FormatterItem::Group { id, .. } if id == candidate))
+        })
+        .unwrap_or_default()
+}
+
+/// First `<name> N` (N from 2) not already used by a group. A trailing number
+/// on `name` is replaced, so splitting "Worship 2" gives "Worship 3".
+fn next_group_name(items: &[FormatterItem], name: &str) -> String {
+    let base = match name.rsplit_once(' ') {
+        Some((base, n)) if !base.is_empty() && !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => base,
+        _ => name,
+    };
+    (2..)
+        .map(|n| format!("{} {}", base, n))
+        .find(|candidate| {
+            !items.iter().any(|item| matches!(item, FormatterItem::Group { name, .. } if name == candidate))
+        })
+        .unwrap_or_default()
+}
+
+/// Wrap the entries at `paths` in a new group placed where the first of them
+/// was. Returns the new group's index.
+pub fn wrap_in_group(items: &mut Vec<FormatterItem>, paths: &[EntryPath], name: String, color: String) -> Option<usize> {
+    let first = *paths.iter().min()?;
+    // Grouped entries leave their group behind, so the new group goes after it
+    let position = if first.sub_idx.is_some() { first.item_idx + 1 } else { first.item_idx };
+    let position = index_after_take(items, paths, position);
+    let id = next_group_id(items);
+    let entries = take_entries(items, paths);
+    if entries.is_empty() {
+        return None;
+    }
+    let position = position.min(items.len());
+    items.insert(position, FormatterItem::Group { id, name, color, entries, collapsed: false, target_duration: None });
+    Some(position)
+}
+
+/// Move the entries from `sub_idx` onwards into a new group right after this one
+pub fn split_group(items: &mut Vec<FormatterItem>, group_idx: usize, sub_idx: usize) {
+    let id = next_group_id(items);
+    let Some(FormatterItem::Group { name, .. }) = items.get(group_idx) else { return };
+    let new_name = next_group_name(items, name);
+    let Some(FormatterItem::Group { color, entries, .. }) = items.get_mut(group_idx) else { return };
+    if sub_idx == 0 || sub_idx >= entries.len() {
+        return;
+    }
+    let tail = entries.split_off(sub_idx);
+    let group = FormatterItem::Group {
+        id,
+        name: new_name,
+        color: color.clone(),
+        entries: tail,
+        collapsed: false,
+        target_duration: None,
+    };
+    items.insert(group_idx + 1, group);
+}
+
+/// Append the next group's entries to this one and remove it. Only adjacent groups merge.
+pub fn merge_with_next_group(items: &mut Vec<FormatterItem>, group_idx: usize) {
+    if !matches!(items.get(group_idx + 1), Some(FormatterItem::Group { .. })) {
+        return;
+    }
+    let FormatterItem::Group { entries: next, .. } = items.remove(group_idx + 1) else { return };
+    if let Some(FormatterItem::Group { entries, .. }) = items.get_mut(group_idx) {
+        entries.extend(next);
+    }
+}
+
+/// Replace a group with its entries as standalone items
+pub fn ungroup(items: &mut Vec<FormatterItem>, group_idx: usize) {
+    if !matches!(items.get(group_idx), Some(FormatterItem::Group { .. })) {
+        return;
+    }
+    let FormatterItem::Group { entries, .. } = items.remove(group_idx) else { return };
+    for (offset, entry) in entries.into_iter().enumerate() {
+        items.insert(group_idx + offset, FormatterItem::Standalone(entry));
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TimeEditContext {
     pub item_idx: usize,
     pub sub_item_idx: Option<usize>,
     pub field: TimeField,
-    pub current_value: String,
+    /// Duration, or end time since midnight of the first day (ms)
+    pub current_value: u64,
 }
 
 /// Unified item type for the formatter - can be standalone or a group
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
 pub enum FormatterItem {
     Standalone(TimedEntry),
     Group {
@@ -52,12 +575,372 @@ pub enum FormatterItem {
         color: String,
         entries: Vec<TimedEntry>,
         collapsed: bool,
+        /// Time budget for the whole group, pushed as Ontime's `targetDuration`
+        #[serde(default)]
+        target_duration: Option<Duration>,
     },
     Reference {
         id: String,
+        /// Ontime cue, used to find the entry again if its id no longer exists
+        #[serde(default)]
+        cue: String,
         title: String,
         item_type: String,
         mode: InsertionMode,
-        time_end: u64, // End time of the referenced event (ms)
+        time_end: TimeOfDay, // End time of the referenced event
+    },
+    /// Planned Ontime delay block, pushing everything after it back by `duration`
+    Delay {
+        id: String,
+        duration: Duration,
     },
+    /// Ontime milestone marker ("Doors open"), which takes no time in the rundown
+    Milestone {
+        id: String,
+        title: String,
+        cue: String,
+        note: String,
+        color: String,
+    },
+}
+
+impl FormatterItem {
+    pub fn new_delay(duration: Duration) -> Self {
+        FormatterItem::Delay { id: TimedEntry::new_instance_id(), duration }
+    }
+
+    pub fn new_milestone(title: impl Into<String>) -> Self {
+        FormatterItem::Milestone {
+            id: TimedEntry::new_instance_id(),
+            title: title.into(),
+            cue: String::new(),
+            note: String::new(),
+            color: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: u64 = 60_000;
+
+    fn at(minutes: u64) -> TimeOfDay {
+        TimeOfDay::from_schedule_ms(minutes * MIN)
+    }
+
+    fn entry(name: &str, minutes: u64, end: Option<u64>) -> TimedEntry {
+        TimedEntry::manual(name.to_string(), Duration::from_ms(minutes * MIN), end.map(at).unwrap_or(TimeOfDay::MIDNIGHT))
+    }
+
+    fn locked(name: &str, minutes: u64, end: u64) -> TimedEntry {
+        TimedEntry { locked: true, ..entry(name, minutes, Some(end)) }
+    }
+
+    fn standalone(entries: Vec<TimedEntry>) -> Vec<FormatterItem> {
+        entries.into_iter().map(FormatterItem::Standalone).collect()
+    }
+
+    fn group(name: &str, entries: Vec<TimedEntry>) -> FormatterItem {
+        FormatterItem::Group {
+            id: format!("id-{}", name),
+            name: name.to_string(),
+            color: String::new(),
+            entries,
+            collapsed: false,
+            target_duration: None,
+        }
+    }
+
+    fn end_times(items: &[FormatterItem]) -> Vec<TimeOfDay> {
+        EntryPath::all(items).into_iter().filter_map(|p| p.entry(items)).map(|e| e.end_time).collect()
+    }
+
+    #[test]
+    fn anchor_conflicts_count_chained_durations() {
+        // Welcome ends 10:00, two songs without end times run to 10:20, the locked sermon starts 10:15
+        let sermon = locked("Sermon", 30, 10 * 60 + 45);
+        let items = standalone(vec![
+            entry("Welcome", 5, Some(10 * 60)),
+            entry("Song 1", 10, None),
+            entry("Song 2", 10, None),
+            sermon.clone(),
+        ]);
+        let conflicts = anchor_conflicts(&items);
+        assert_eq!(conflicts.get(&sermon.instance_id), Some(&Duration::from_ms(5 * MIN)));
+        assert_eq!(conflicts.len(), 1);
+    }
+
+    #[test]
+    fn anchor_conflicts_follow_delays_references_and_groups() {
+        let end = locked("Service end", 5, 11 * 60);
+        let mut items = standalone(vec![entry("Welcome", 5, Some(10 * 60))]);
+        items.push(group("Worship", vec![entry("Song", 50, None)]));
+        items.push(FormatterItem::new_delay(Duration::from_ms(10 * MIN)));
+        items.push(FormatterItem::Standalone(end.clone()));
+        // 10:00 + 50m + 10m delay = 11:00, five minutes into the 10:55 start
+        assert_eq!(anchor_conflicts(&items).get(&end.instance_id), Some(&Duration::from_ms(5 * MIN)));
+
+        // A reference re-anchors the chain before the locked entry
+        items.insert(3, FormatterItem::Reference {
+            id: "ref".to_string(),
+            cue: String::new(),
+            title: "Announcements".to_string(),
+            item_type: "event".to_string(),
+            mode: InsertionMode::After,
+            time_end: at(10 * 60 + 50),
+        });
+        assert!(anchor_conflicts(&items).is_empty());
+    }
+
+    #[test]
+    fn anchor_conflicts_ignore_plans_that_fit() {
+        let items = standalone(vec![
+            entry("Welcome", 5, Some(10 * 60)),
+            entry("Song", 10, None),
+            locked("Sermon", 30, 10 * 60 + 45),
+        ]);
+        assert!(anchor_conflicts(&items).is_empty());
+        // Nothing pins the time down before the locked entry
+        let items = standalone(vec![entry("Song", 90, None), locked("Sermon", 30, 10 * 60 + 45)]);
+        assert!(anchor_conflicts(&items).is_empty());
+    }
+
+    #[test]
+    fn ripple_end_times_stops_at_locked_entry() {
+        let mut items = standalone(vec![
+            entry("A", 5, Some(600)),
+            entry("B", 5, Some(605)),
+            entry("C", 5, None),
+            locked("D", 5, 620),
+            entry("E", 5, Some(625)),
+        ]);
+        ripple_end_times(&mut items, EntryPath::standalone(0), 2 * MIN as i64);
+        assert_eq!(end_times(&items), [at(600), at(607), TimeOfDay::MIDNIGHT, at(620), at(625)]);
+    }
+
+    #[test]
+    fn ripple_end_times_crosses_groups() {
+        let mut items = vec![
+            group("One", vec![entry("A", 5, Some(600)), entry("B", 5, Some(605))]),
+            group("Two", vec![entry("C", 5, Some(610))]),
+        ];
+        ripple_end_times(&mut items, EntryPath::in_group(0, 0), -(MIN as i64));
+        assert_eq!(end_times(&items), [at(600), at(604), at(609)]);
+    }
+
+    #[test]
+    fn ripple_edit_moves_later_entries() {
+        let mut items = standalone(vec![entry("A", 5, Some(600)), entry("B", 5, Some(605))]);
+        // Lengthening A by two minutes pushes its end and B's back
+        let path = EntryPath::standalone(0);
+        path.entry_mut(&mut items).unwrap().duration = Duration::from_ms(7 * MIN);
+        ripple_edit(&mut items, path, at(600), Duration::from_ms(5 * MIN), TimeField::Duration);
+        assert_eq!(end_times(&items), [at(602), at(607)]);
+
+        // Moving A's end earlier pulls B with it
+        path.entry_mut(&mut items).unwrap().end_time = at(598);
+        ripple_edit(&mut items, path, at(602), Duration::from_ms(7 * MIN), TimeField::EndTime);
+        assert_eq!(end_times(&items), [at(598), at(603)]);
+    }
+
+    #[test]
+    fn ripple_edit_keeps_locked_and_unset_ends() {
+        let mut items = standalone(vec![locked("A", 5, 600), entry("B", 5, Some(605))]);
+        let path = EntryPath::standalone(0);
+        path.entry_mut(&mut items).unwrap().duration = Duration::from_ms(7 * MIN);
+        ripple_edit(&mut items, path, at(600), Duration::from_ms(5 * MIN), TimeField::Duration);
+        assert_eq!(end_times(&items), [at(600), at(605)]);
+
+        let mut items = standalone(vec![entry("A", 5, None), entry("B", 5, Some(605))]);
+        path.entry_mut(&mut items).unwrap().duration = Duration::from_ms(7 * MIN);
+        ripple_edit(&mut items, path, TimeOfDay::MIDNIGHT, Duration::from_ms(5 * MIN), TimeField::Duration);
+        assert_eq!(end_times(&items), [TimeOfDay::MIDNIGHT, at(605)]);
+    }
+
+    fn durations(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().map(|ms| Duration::from_ms(*ms)).collect()
+    }
+
+    #[test]
+    fn fit_durations_scales_and_rounds() {
+        // 10:00 + 20:00 into 20:00 scales each by two thirds, to whole seconds
+        let entries = [(Duration::from_ms(600_000), false), (Duration::from_ms(1_200_000), false)];
+        assert_eq!(fit_durations(&entries, Duration::from_ms(1_200_000)), Ok(durations(&[400_000, 800_000])));
+
+        // Three equal entries into 100s: 33s, 33s and the last takes the 34s remainder
+        let equal = [(Duration::from_ms(60_000), false); 3];
+        assert_eq!(fit_durations(&equal, Duration::from_ms(100_000)), Ok(durations(&[33_000, 33_000, 34_000])));
+    }
+
+    #[test]
+    fn fit_durations_keeps_fixed_and_empty_entries() {
+        let entries = [
+            (Duration::from_ms(300_000), true),
+            (Duration::from_ms(600_000), false),
+            (Duration::from_ms(0), false),
+            (Duration::from_ms(600_000), false),
+        ];
+        let fitted = fit_durations(&entries, Duration::from_ms(900_000));
+        assert_eq!(fitted, Ok(durations(&[300_000, 300_000, 0, 300_000])));
+    }
+
+    #[test]
+    fn fit_durations_rejects_impossible_targets() {
+        let fixed_over = [(Duration::from_ms(600_000), true), (Duration::from_ms(60_000), false)];
+        assert!(fit_durations(&fixed_over, Duration::from_ms(300_000)).unwrap_err().starts_with("Fixed entries alone take"));
+
+        let all_fixed = [(Duration::from_ms(60_000), true), (Duration::from_ms(60_000), true)];
+        assert_eq!(
+            fit_durations(&all_fixed, Duration::from_ms(300_000)),
+            Err("No flexible entries with a duration to scale".to_string())
+        );
+
+        // Rounding the first two up to a second each leaves nothing for the last
+        let tight = [(Duration::from_ms(60_000), false); 3];
+        assert!(fit_durations(&tight, Duration::from_ms(2_000)).unwrap_err().contains("too tight"));
+    }
+
+    fn names(items: &[FormatterItem]) -> Vec<String> {
+        items
+            .iter()
+            .map(|item| match item {
+                FormatterItem::Standalone(entry) => entry.name.clone(),
+                FormatterItem::Group { name, entries, .. } => {
+                    format!("{}[{}]", name, entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>().join(","))
+                }
+                _ => "?".to_string(),
+            })
+            .collect()
+    }
+
+    /// A, G1[B,C], D, G2[E]
+    fn plan() -> Vec<FormatterItem> {
+        vec![
+            FormatterItem::Standalone(entry("A", 5, None)),
+            group("G1", vec![entry("B", 5, None), entry("C", 5, None)]),
+            FormatterItem::Standalone(entry("D", 5, None)),
+            group("G2", vec![entry("E", 5, None)]),
+        ]
+    }
+
+    #[test]
+    fn take_entries_drops_emptied_groups() {
+        let mut items = plan();
+        let paths = [EntryPath::in_group(3, 0), EntryPath::standalone(0), EntryPath::in_group(1, 1)];
+        assert_eq!(index_after_take(&items, &paths, 2), 1);
+        assert_eq!(index_after_take(&items, &paths, 4), 2);
+        let taken = take_entries(&mut items, &paths);
+        assert_eq!(taken.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), ["A", "C", "E"]);
+        assert_eq!(names(&items), ["G1[B]", "D"]);
+    }
+
+    #[test]
+    fn move_entry_shifts_indices() {
+        // Standalone A into the end of G2, which moves up by one
+        let mut items = plan();
+        move_entry(&mut items, EntryPath::standalone(0), DropTarget::GroupEnd(3));
+        assert_eq!(names(&items), ["G1[B,C]", "D", "G2[E,A]"]);
+
+        // Within a group, towards the end
+        let mut items = plan();
+        move_entry(&mut items, EntryPath::in_group(1, 0), DropTarget::GroupEnd(1));
+        assert_eq!(names(&items), ["A", "G1[C,B]", "D", "G2[E]"]);
+
+        // Out of a group to the top level
+        let mut items = plan();
+        move_entry(&mut items, EntryPath::in_group(1, 1), DropTarget::Gap(0));
+        assert_eq!(names(&items), ["C", "A", "G1[B]", "D", "G2[E]"]);
+
+        // The last entry of G2 leaves it, and G2 goes
+        let mut items = plan();
+        move_entry(&mut items, EntryPath::in_group(3, 0), DropTarget::GroupEntry(1, 1));
+        assert_eq!(names(&items), ["A", "G1[B,E,C]", "D"]);
+
+        // A group's only entry moved within it stays put
+        let mut items = plan();
+        move_entry(&mut items, EntryPath::in_group(3, 0), DropTarget::GroupEnd(3));
+        assert_eq!(names(&items), names(&plan()));
+    }
+
+    #[test]
+    fn move_entry_past_an_emptied_group() {
+        let mut items = vec![
+            group("G1", vec![entry("A", 5, None)]),
+            FormatterItem::Standalone(entry("B", 5, None)),
+            group("G2", vec![entry("C", 5, None)]),
+        ];
+        move_entry(&mut items, EntryPath::in_group(0, 0), DropTarget::GroupEntry(2, 0));
+        assert_eq!(names(&items), ["B", "G2[A,C]"]);
+
+        let mut items = vec![group("G1", vec![entry("A", 5, None)]), FormatterItem::Standalone(entry("B", 5, None))];
+        move_entry(&mut items, EntryPath::in_group(0, 0), DropTarget::Gap(2));
+        assert_eq!(names(&items), ["B", "A"]);
+    }
+
+    #[test]
+    fn move_entries_to_group_keeps_the_target() {
+        let mut items = plan();
+        let paths = [EntryPath::standalone(0), EntryPath::in_group(1, 0), EntryPath::in_group(3, 0)];
+        move_entries_to_group(&mut items, &paths, 1);
+        assert_eq!(names(&items), ["G1[B,C,A,E]", "D"]);
+
+        let mut items = plan();
+        let paths = [EntryPath::in_group(1, 0), EntryPath::in_group(1, 1), EntryPath::standalone(2)];
+        move_entries_to_group(&mut items, &paths, 3);
+        assert_eq!(names(&items), ["A", "G2[E,B,C,D]"]);
+    }
+
+    #[test]
+    fn wrap_in_group_takes_the_first_place() {
+        let mut items = plan();
+        let paths = [EntryPath::standalone(2), EntryPath::standalone(0)];
+        let at = wrap_in_group(&mut items, &paths, "New".to_string(), String::new());
+        assert_eq!(at, Some(0));
+        assert_eq!(names(&items), ["New[A,D]", "G1[B,C]", "G2[E]"]);
+
+        // Emptying G1 puts the new group where it was
+        let mut items = plan();
+        let paths = [EntryPath::in_group(1, 0), EntryPath::in_group(1, 1), EntryPath::standalone(2)];
+        let at = wrap_in_group(&mut items, &paths, "New".to_string(), String::new());
+        assert_eq!(at, Some(1));
+        assert_eq!(names(&items), ["A", "New[B,C,D]", "G2[E]"]);
+
+        // Part of G1 goes in a group after it
+        let mut items = plan();
+        let at = wrap_in_group(&mut items, &[EntryPath::in_group(1, 1)], "New".to_string(), String::new());
+        assert_eq!(at, Some(2));
+        assert_eq!(names(&items), ["A", "G1[B]", "New[C]", "D", "G2[E]"]);
+    }
+
+    #[test]
+    fn split_merge_and_ungroup() {
+        let mut items = plan();
+        split_group(&mut items, 1, 1);
+        assert_eq!(names(&items), ["A", "G1[B]", "G1 2[C]", "D", "G2[E]"]);
+        // Splitting at either end does nothing
+        split_group(&mut items, 1, 0);
+        split_group(&mut items, 1, 1);
+        assert_eq!(items.len(), 5);
+
+        merge_with_next_group(&mut items, 1);
+        assert_eq!(names(&items), ["A", "G1[B,C]", "D", "G2[E]"]);
+        // Only adjacent groups merge
+        merge_with_next_group(&mut items, 1);
+        assert_eq!(items.len(), 4);
+
+        ungroup(&mut items, 1);
+        assert_eq!(names(&items), ["A", "B", "C", "D", "G2[E]"]);
+        ungroup(&mut items, 0);
+        assert_eq!(items.len(), 5);
+    }
+
+    #[test]
+    fn next_group_name_counts_up() {
+        let items = vec![group("Worship", vec![]), group("Worship 2", vec![])];
+        assert_eq!(next_group_name(&items, "Worship"), "Worship 3");
+        assert_eq!(next_group_name(&items, "Worship 2"), "Worship 3");
+        assert_eq!(next_group_name(&items, "Prayer"), "Prayer 2");
+    }
 }
@@ -0,0 +1,363 @@
+use super::formatter::{next_group_id, FormatterItem, TimedEntry};
+use super::propresenter::PlaylistItem;
+use super::rules::{apply_rules, NameRule, RuleSet};
+use super::time::{Duration, TimeOfDay};
+use crate::utils::{parse_duration, parse_end_time};
+
+/// What a spreadsheet column is imported as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportField {
+    Ignore,
+    Title,
+    Duration,
+    EndTime,
+    Group,
+    Cue,
+    Note,
+}
+
+impl ImportField {
+    pub const ALL: [ImportField; 7] = [
+        ImportField::Ignore,
+        ImportField::Title,
+        ImportField::Duration,
+        ImportField::EndTime,
+        ImportField::Group,
+        ImportField::Cue,
+        ImportField::Note,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportField::Ignore => "Ignore",
+            ImportField::Title => "Title",
+            ImportField::Duration => "Duration",
+            ImportField::EndTime => "End time",
+            ImportField::Group => "Group",
+            ImportField::Cue => "Cue",
+            ImportField::Note => "Note",
+        }
+    }
+
+    /// Best guess from a header cell, e.g. "Song title" → Title, "Length" → Duration.
+    /// Keywords match whole words (plurals too), so "Sender" isn't an end time.
+    pub fn guess(header: &str) -> Self {
+        let header = header.trim().to_lowercase();
+        let words: Vec<&str> = header.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+        let is_word = |word: &str, keyword: &str| word == keyword || word.strip_suffix('s') == Some(keyword);
+        let has = |keywords: &[&str]| {
+            keywords.iter().any(|keyword| {
+                let phrase: Vec<&str> = keyword.split(' ').collect();
+                words
+                    .windows(phrase.len())
+                    .any(|window| window.iter().zip(&phrase).all(|(word, keyword)| is_word(word, keyword)))
+            })
+        };
+        if has(&["duration", "length", "dur", "time allotted"]) {
+            ImportField::Duration
+        } else if has(&["end", "finish", "until"]) {
+            ImportField::EndTime
+        } else if has(&["group", "section", "segment", "block"]) {
+            ImportField::Group
+        } else if has(&["cue"]) {
+            ImportField::Cue
+        } else if has(&["note", "comment", "remark"]) {
+            ImportField::Note
+        } else if has(&["title", "name", "item", "song", "element"]) {
+            ImportField::Title
+        } else {
+            ImportField::Ignore
+        }
+    }
+}
+
+/// A CSV/TSV sheet read as text: the header row and the data rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl ImportTable {
+    /// Parse delimited text, picking tab, semicolon or comma from the header line
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim_start_matches('\u{feff}');
+        let first_line = text.lines().next().unwrap_or_default();
+        let delimiter = if first_line.contains('\t') {
+            b'\t'
+        } else if first_line.matches(';').count() > first_line.matches(',').count() {
+            b';'
+        } else {
+            b','
+        };
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_reader(text.as_bytes());
+        let headers: Vec<String> = reader
+            .headers()
+            .map_err(|e| format!("Can't read the header row: {}", e))?
+            .iter()
+            .map(|h| h.trim().to_string())
+            .collect();
+        if headers.iter().all(|h| h.is_empty()) {
+            return Err("The file has no header row".to_string());
+        }
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|e| format!("Can't read row {}: {}", rows.len() + 2, e))?;
+            let row: Vec<String> = record.iter().map(|cell| cell.trim().to_string()).collect();
+            if row.iter().any(|cell| !cell.is_empty()) {
+                rows.push(row);
+            }
+        }
+        Ok(Self { headers, rows })
+    }
+
+    /// Guessed field for each column. Only the first column guessed as a field keeps it.
+    pub fn guess_mapping(&self) -> Vec<ImportField> {
+        let mut taken = Vec::new();
+        self.headers
+            .iter()
+            .map(|header| match ImportField::guess(header) {
+                field if field != ImportField::Ignore && !taken.contains(&field) => {
+                    taken.push(field);
+                    field
+                }
+                _ => ImportField::Ignore,
+            })
+            .collect()
+    }
+
+    /// The cell of `row` mapped to `field`, if any column is
+    pub fn cell<'a>(&self, row: &'a [String], mapping: &[ImportField], field: ImportField) -> &'a str {
+        mapping
+            .iter()
+            .position(|f| *f == field)
+            .and_then(|col| row.get(col))
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+}
+
+/// Formatter items built from a sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportResult {
+    pub items: Vec<FormatterItem>,
+    /// Rows that matched a playlist item by name
+    pub matched: usize,
+    /// Titles that became manual entries
+    pub unmatched: Vec<String>,
+    /// Cells that couldn't be read, e.g. "Row 4: ..."
+    pub warnings: Vec<String>,
+}
+
+/// Playlist item named `title`, preferring one not already used by an earlier row
+pub fn find_playlist_match(playlist: &[PlaylistItem], used: &[bool], title: &str) -> Option<usize> {
+    let title = title.trim();
+    let matches = |i: &usize| playlist[*i].id.name.trim().eq_ignore_ascii_case(title);
+    (0..playlist.len())
+        .filter(matches)
+        .find(|&i| !used[i])
+        .or_else(|| (0..playlist.len()).find(matches))
+}
+
+/// Turn sheet rows into formatter items. Rows that name a playlist item become
+/// its entry (with rules applied); the rest become manual entries. The sheet's
+/// own values win over rules, and consecutive rows sharing a group name share a group.
+pub fn build_import(
+    table: &ImportTable,
+    mapping: &[ImportField],
+    playlist: &[PlaylistItem],
+    rules: &[NameRule],
+    default_duration: Duration,
+) -> ImportResult {
+    let rules = RuleSet::new(rules);
+    let mut items: Vec<FormatterItem> = Vec::new();
+    let mut used = vec![false; playlist.len()];
+    let mut matched = 0;
+    let mut unmatched = Vec::new();
+    let mut warnings = Vec::new();
+    let mut previous_end = TimeOfDay::MIDNIGHT;
+
+    for (row_idx, row) in table.rows.iter().enumerate() {
+        let line = row_idx + 2;
+        let title = table.cell(row, mapping, ImportField::Title);
+        if title.is_empty() {
+            continue;
+        }
+
+        let mut entry = match find_playlist_match(playlist, &used, title) {
+            Some(i) => {
+                used[i] = true;
+                matched += 1;
+                let mut entry = TimedEntry::from_playlist_item(&playlist[i], default_duration, TimeOfDay::MIDNIGHT);
+                apply_rules(&rules, &playlist[i], &mut entry);
+                entry
+            }
+            None => {
+                unmatched.push(title.to_string());
+                TimedEntry::manual(title.to_string(), default_duration, TimeOfDay::MIDNIGHT)
+            }
+        };
+
+        match table.cell(row, mapping, ImportField::Duration) {
+            "" => {}
+            text => match parse_duration(text) {
+                Ok(duration) => entry.duration = duration,
+                Err(e) => warnings.push(format!("Row {}: duration {}", line, e)),
+            },
+        }
+        match table.cell(row, mapping, ImportField::EndTime) {
+            "" => {}
+            text => match parse_end_time(text) {
+                // Sheets rarely mark the day, so an earlier time means the show crossed midnight
+                Ok(end) => {
+                    entry.end_time = end.roll_forward_from(previous_end);
+                    previous_end = entry.end_time;
+                }
+                Err(e) => warnings.push(format!("Row {}: end time {}", line, e)),
+            },
+        }
+        let cue = table.cell(row, mapping, ImportField::Cue);
+        if !cue.is_empty() {
+            entry.cue = cue.to_string();
+        }
+        let note = table.cell(row, mapping, ImportField::Note);
+        if !note.is_empty() {
+            entry.note = note.to_string();
+        }
+
+        let group = table.cell(row, mapping, ImportField::Group);
+        if group.is_empty() {
+            items.push(FormatterItem::Standalone(entry));
+            continue;
+        }
+        // Only the group just before continues, so a name that comes back later starts a new group
+        let existing = match items.last_mut() {
+            Some(FormatterItem::Group { name, entries, .. }) if name.eq_ignore_ascii_case(group) => Some(entries),
+            _ => None,
+        };
+        match existing {
+            Some(entries) => entries.push(entry),
+            None => {
+                let id = next_group_id(&items);
+                items.push(FormatterItem::Group {
+                    id,
+                    name: group.to_string(),
+                    color: "#779BE7".to_string(),
+                    entries: vec![entry],
+                    collapsed: false,
+                    target_duration: None,
+                });
+            }
+        }
+    }
+
+    ImportResult { items, matched, unmatched, warnings }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Dictionary;
+
+    fn playlist_item(name: &str, index: usize) -> PlaylistItem {
+        PlaylistItem {
+            id: Dictionary { uuid: format!("uuid-{}", index), name: name.to_string(), index },
+            item_type: "presentation".to_string(),
+            duration: None,
+            presentation_info: None,
+        }
+    }
+
+    fn table(headers: &[&str], rows: &[&[&str]]) -> ImportTable {
+        ImportTable {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: rows.iter().map(|row| row.iter().map(|c| c.to_string()).collect()).collect(),
+        }
+    }
+
+    fn group_names(items: &[FormatterItem]) -> Vec<String> {
+        items
+            .iter()
+            .map(|item| match item {
+                FormatterItem::Group { name, entries, .. } => format!("{} ({})", name, entries.len()),
+                FormatterItem::Standalone(entry) => entry.name.clone(),
+                _ => String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_picks_the_delimiter() {
+        let comma = ImportTable::parse("\u{feff}Title,Length\nWelcome,5:00\n\n\"Song, live\",4:30\n").unwrap();
+        assert_eq!(comma.headers, ["Title", "Length"]);
+        assert_eq!(comma.rows, [vec!["Welcome", "5:00"], vec!["Song, live", "4:30"]]);
+
+        let tabs = ImportTable::parse("Title\tNote\nWelcome\t Hello, all \n").unwrap();
+        assert_eq!(tabs.rows, [vec!["Welcome", "Hello, all"]]);
+
+        let semicolons = ImportTable::parse("Title;Length;Note\nWelcome;5:00;a, b\nShort row\n").unwrap();
+        assert_eq!(semicolons.rows, [vec!["Welcome", "5:00", "a, b"], vec!["Short row"]]);
+
+        assert!(ImportTable::parse(",,\na,b,c\n").is_err());
+    }
+
+    #[test]
+    fn guess_mapping_matches_whole_words() {
+        let sheet = table(&["Song title", "Sender", "Attendees", "Length", "End time", "Section", "Notes", "Title"], &[]);
+        assert_eq!(
+            sheet.guess_mapping(),
+            [
+                ImportField::Title,
+                ImportField::Ignore,
+                ImportField::Ignore,
+                ImportField::Duration,
+                ImportField::EndTime,
+                ImportField::Group,
+                ImportField::Note,
+                // Only the first title column keeps the field
+                ImportField::Ignore,
+            ]
+        );
+        assert_eq!(ImportField::guess("Time allotted"), ImportField::Duration);
+        assert_eq!(ImportField::guess("Legend"), ImportField::Ignore);
+        assert_eq!(ImportField::guess("Cues"), ImportField::Cue);
+    }
+
+    #[test]
+    fn build_import_keeps_the_run_order() {
+        let sheet = table(
+            &["Group", "Title", "Duration", "End"],
+            &[
+                &["Worship", "Amazing Grace", "4:00", ""],
+                &["Worship", "Cornerstone", "", ""],
+                &["", "Notices", "2m", ""],
+                &["worship", "Amazing Grace", "bad", "23:30"],
+                &["", "Close", "", "00:15"],
+                &["", "", "5:00", ""],
+            ],
+        );
+        let mapping = sheet.guess_mapping();
+        let playlist = vec![playlist_item("Amazing Grace", 0), playlist_item("Cornerstone", 1)];
+        let result = build_import(&sheet, &mapping, &playlist, &[], Duration::from_ms(300_000));
+
+        assert_eq!(group_names(&result.items), ["Worship (2)", "Notices", "worship (1)", "Close"]);
+        assert_eq!(result.matched, 3);
+        assert_eq!(result.unmatched, ["Notices", "Close"]);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].starts_with("Row 5: duration"));
+
+        let FormatterItem::Group { entries, id, .. } = &result.items[0] else { panic!("expected a group") };
+        assert_eq!(entries[0].duration, Duration::from_ms(240_000));
+        assert_eq!(entries[1].duration, Duration::from_ms(300_000));
+        assert!(!entries[0].manual);
+        let FormatterItem::Group { id: second_id, .. } = &result.items[2] else { panic!("expected a group") };
+        assert_ne!(id, second_id);
+        // The close crossed midnight after the 23:30 row
+        let FormatterItem::Standalone(close) = &result.items[3] else { panic!("expected an entry") };
+        assert!(close.manual);
+        assert_eq!(close.end_time, TimeOfDay::new(15 * 60_000, 1));
+    }
+}
@@ -4,8 +4,34 @@ mod settings;
 mod propresenter;
 mod ontime;
 mod formatter;
+mod time;
+mod project;
+mod session;
+mod history;
+mod template;
+mod rules;
+mod rebalance;
+mod rehearsal;
+mod timing_history;
+mod report;
+mod import;
+mod export;
+mod run_sheet;
 
 pub use settings::*;
 pub use propresenter::*;
 pub use ontime::*;
 pub use formatter::*;
+pub use time::*;
+pub use project::*;
+pub use session::*;
+pub use history::*;
+pub use template::*;
+pub use rules::*;
+pub use rebalance::*;
+pub use rehearsal::*;
+pub use timing_history::*;
+pub use report::*;
+pub use import::*;
+pub use export::*;
+pub use run_sheet::*;
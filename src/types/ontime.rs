@@ -1,5 +1,6 @@
 use serde::Deserialize;
 use std::collections::HashMap;
+use super::formatter::InsertionMode;
 
 #[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct OntimeEvent {
@@ -28,6 +29,8 @@ pub struct OntimeEntry {
     pub time_start: u64,
     #[serde(rename = "timeEnd", default)]
     pub time_end: u64,
+    #[serde(rename = "dayOffset", default)]
+    pub day_offset: u32,
     #[serde(default)]
     pub parent: Option<String>,
     // Allow unknown fields to be ignored
@@ -48,3 +51,215 @@ pub struct OntimeRundown {
     #[serde(default)]
     pub revision: u64,
 }
+
+/// Parent/after/before for the next entry posted to the rundown
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InsertionPoint {
+    pub parent: Option<String>,
+    pub after: Option<String>,
+    /// Only used where there is nothing to go after, e.g. ahead of the first entry
+    pub before: Option<String>,
+}
+
+impl InsertionPoint {
+    pub fn after(id: Option<String>) -> Self {
+        Self { parent: None, after: id, before: None }
+    }
+
+    pub fn apply(&self, payload: &mut serde_json::Value) {
+        if let Some(ref parent) = self.parent {
+            payload["parent"] = serde_json::json!(parent);
+        }
+        if let Some(ref after) = self.after {
+            payload["after"] = serde_json::json!(after);
+        } else if let Some(ref before) = self.before {
+            payload["before"] = serde_json::json!(before);
+        }
+    }
+
+    /// Where a group goes from here. Ontime doesn't nest groups, so inside a
+    /// group this is straight after that group, at the top level.
+    pub fn for_group(&self) -> Self {
+        match &self.parent {
+            Some(parent) => Self::after(Some(parent.clone())),
+            None => self.clone(),
+        }
+    }
+
+    /// Chain the next entry on after one that was just created
+    pub fn advance(&mut self, created_id: String) {
+        self.after = Some(created_id);
+        self.before = None;
+    }
+}
+
+impl OntimeRundown {
+    /// Find a referenced entry by id, falling back to its cue when the id is gone
+    pub fn resolve_reference(&self, id: &str, cue: &str) -> Option<&OntimeEntry> {
+        self.entries.get(id).or_else(|| {
+            let cue = cue.trim();
+            if cue.is_empty() {
+                return None;
+            }
+            self.flat_order
+                .iter()
+                .filter_map(|entry_id| self.entries.get(entry_id))
+                .find(|entry| entry.cue.eq_ignore_ascii_case(cue))
+        })
+    }
+
+    /// Whether the server knows about group target durations. Older versions
+    /// don't send `targetDuration` on groups; with no groups to go by, assume it does.
+    pub fn supports_group_target(&self) -> bool {
+        let mut groups = self.entries.values().filter(|e| e.entry_type == "group").peekable();
+        groups.peek().is_none() || groups.any(|g| g._extra.contains_key("targetDuration"))
+    }
+
+    /// Entries sharing a parent, in rundown order
+    fn children_of(&self, parent: Option<&str>) -> Vec<&str> {
+        self.flat_order
+            .iter()
+            .filter(|id| self.entries.get(*id).is_some_and(|e| e.parent.as_deref() == parent))
+            .map(|id| id.as_str())
+            .collect()
+    }
+
+    /// Work out where items following a reference go
+    pub fn insertion_point(&self, target: &OntimeEntry, mode: InsertionMode) -> Result<InsertionPoint, String> {
+        let is_group = target.entry_type == "group";
+        match mode {
+            InsertionMode::Before => {
+                let siblings = self.children_of(target.parent.as_deref());
+                let pos = siblings.iter().position(|id| *id == target.id);
+                let previous = pos.and_then(|p| p.checked_sub(1)).map(|p| siblings[p].to_string());
+                Ok(InsertionPoint {
+                    parent: target.parent.clone(),
+                    before: previous.is_none().then(|| target.id.clone()),
+                    after: previous,
+                })
+            }
+            InsertionMode::After => {
+                let top_level = target.parent.clone().unwrap_or_else(|| target.id.clone());
+                Ok(InsertionPoint::after(Some(top_level)))
+            }
+            InsertionMode::AfterInGroup => Ok(InsertionPoint {
+                parent: target.parent.clone(),
+                after: Some(target.id.clone()),
+                before: None,
+            }),
+            InsertionMode::IntoStart | InsertionMode::IntoEnd if !is_group => {
+                Err(format!("\"{}\" is not a group", target.title))
+            }
+            InsertionMode::IntoStart => Ok(InsertionPoint {
+                parent: Some(target.id.clone()),
+                after: None,
+                before: self.children_of(Some(&target.id)).first().map(|id| id.to_string()),
+            }),
+            InsertionMode::IntoEnd => Ok(InsertionPoint {
+                parent: Some(target.id.clone()),
+                after: self.children_of(Some(&target.id)).last().map(|id| id.to_string()),
+                before: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ev1, grp[a, b], ev2, empty
+    fn rundown() -> OntimeRundown {
+        let entry = |id: &str, kind: &str, parent: Option<&str>| {
+            serde_json::json!({
+                "id": id,
+                "type": kind,
+                "title": id.to_uppercase(),
+                "cue": format!("c-{}", id),
+                "parent": parent,
+            })
+        };
+        serde_json::from_value(serde_json::json!({
+            "id": "default",
+            "title": "Sunday",
+            "flatOrder": ["ev1", "grp", "a", "b", "ev2", "empty"],
+            "entries": {
+                "ev1": entry("ev1", "event", None),
+                "grp": entry("grp", "group", None),
+                "a": entry("a", "event", Some("grp")),
+                "b": entry("b", "event", Some("grp")),
+                "ev2": entry("ev2", "event", None),
+                "empty": entry("empty", "group", None),
+            },
+        }))
+        .unwrap()
+    }
+
+    fn point(parent: Option<&str>, after: Option<&str>, before: Option<&str>) -> InsertionPoint {
+        InsertionPoint {
+            parent: parent.map(str::to_string),
+            after: after.map(str::to_string),
+            before: before.map(str::to_string),
+        }
+    }
+
+    fn at(rundown: &OntimeRundown, id: &str, mode: InsertionMode) -> Result<InsertionPoint, String> {
+        rundown.insertion_point(&rundown.entries[id], mode)
+    }
+
+    #[test]
+    fn before_goes_after_the_previous_sibling() {
+        let rundown = rundown();
+        assert_eq!(at(&rundown, "ev2", InsertionMode::Before), Ok(point(None, Some("grp"), None)));
+        assert_eq!(at(&rundown, "b", InsertionMode::Before), Ok(point(Some("grp"), Some("a"), None)));
+        // Nothing to go after at the start of a level
+        assert_eq!(at(&rundown, "a", InsertionMode::Before), Ok(point(Some("grp"), None, Some("a"))));
+        assert_eq!(at(&rundown, "ev1", InsertionMode::Before), Ok(point(None, None, Some("ev1"))));
+    }
+
+    #[test]
+    fn after_modes() {
+        let rundown = rundown();
+        assert_eq!(at(&rundown, "ev1", InsertionMode::After), Ok(point(None, Some("ev1"), None)));
+        // After an entry in a group means after the group
+        assert_eq!(at(&rundown, "a", InsertionMode::After), Ok(point(None, Some("grp"), None)));
+        assert_eq!(at(&rundown, "a", InsertionMode::AfterInGroup), Ok(point(Some("grp"), Some("a"), None)));
+    }
+
+    #[test]
+    fn into_group_modes() {
+        let rundown = rundown();
+        assert_eq!(at(&rundown, "grp", InsertionMode::IntoStart), Ok(point(Some("grp"), None, Some("a"))));
+        assert_eq!(at(&rundown, "grp", InsertionMode::IntoEnd), Ok(point(Some("grp"), Some("b"), None)));
+        assert_eq!(at(&rundown, "empty", InsertionMode::IntoStart), Ok(point(Some("empty"), None, None)));
+        assert_eq!(at(&rundown, "empty", InsertionMode::IntoEnd), Ok(point(Some("empty"), None, None)));
+        assert!(at(&rundown, "ev1", InsertionMode::IntoEnd).is_err());
+    }
+
+    #[test]
+    fn advance_and_groups() {
+        let mut next = point(Some("grp"), None, Some("a"));
+        next.advance("new".to_string());
+        assert_eq!(next, point(Some("grp"), Some("new"), None));
+        // Groups don't nest, so one posted from inside a group follows that group
+        assert_eq!(next.for_group(), point(None, Some("grp"), None));
+        let top = point(None, None, Some("ev1"));
+        assert_eq!(top.for_group(), top);
+
+        let mut payload = serde_json::json!({ "title": "x" });
+        next.apply(&mut payload);
+        assert_eq!(payload, serde_json::json!({ "title": "x", "parent": "grp", "after": "new" }));
+        let mut payload = serde_json::json!({});
+        top.apply(&mut payload);
+        assert_eq!(payload, serde_json::json!({ "before": "ev1" }));
+    }
+
+    #[test]
+    fn references_fall_back_to_the_cue() {
+        let rundown = rundown();
+        assert_eq!(rundown.resolve_reference("gone", "C-B").map(|e| e.id.as_str()), Some("b"));
+        assert!(rundown.resolve_reference("gone", " ").is_none());
+        // No group carries targetDuration here
+        assert!(!rundown.supports_group_target());
+    }
+}
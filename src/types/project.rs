@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use super::formatter::FormatterItem;
+
+/// Current `.pp2ot` format version. Bump when the layout changes and teach
+/// `ProjectFile::load` how to read the older versions.
+pub const PROJECT_FILE_VERSION: u32 = 1;
+pub const PROJECT_FILE_EXTENSION: &str = "pp2ot";
+
+/// A saved show plan: everything built in the Formatter plus the playlist it came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectFile {
+    pub version: u32,
+    /// ProPresenter playlist name at the time of saving
+    pub playlist_name: String,
+    /// ProPresenter playlist UUID, used to check the plan against the live playlist
+    pub playlist_uuid: String,
+    pub items: Vec<FormatterItem>,
+    #[serde(default)]
+    pub saved_at: String,
+}
+
+impl ProjectFile {
+    pub fn new(playlist_name: String, playlist_uuid: String, items: Vec<FormatterItem>) -> Self {
+        Self {
+            version: PROJECT_FILE_VERSION,
+            playlist_name,
+            playlist_uuid,
+            items,
+            saved_at: chrono::Local::now().to_rfc3339(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| format!("Read error: {}", e))?;
+        let json: serde_json::Value =
+            serde_json::from_str(&contents).map_err(|e| format!("Parse error: {}", e))?;
+        let version = json.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        match version {
+            0 => Err("Not a PP2OT project file (missing version)".to_string()),
+            v if v > PROJECT_FILE_VERSION => Err(format!(
+                "Project was saved by a newer version (format {}, this build reads up to {})",
+                v, PROJECT_FILE_VERSION
+            )),
+            _ => serde_json::from_value(json).map_err(|e| format!("Parse error: {}", e)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(self).map_err(|e| format!("Encode error: {}", e))?;
+        std::fs::write(path, contents).map_err(|e| format!("Write error: {}", e))
+    }
+}
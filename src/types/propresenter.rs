@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
+use super::time::{Duration, MS_PER_SECOND};
 
 /// ProPresenter playlist response structure
 #[derive(Debug, Deserialize, PartialEq, Clone)]
@@ -12,6 +13,37 @@ pub struct PlaylistItem {
     pub id: Dictionary,
     #[serde(rename = "type")]
     pub item_type: String,
+    /// Length in seconds, reported for media and audio items
+    #[serde(default, deserialize_with = "seconds_if_number")]
+    pub duration: Option<f64>,
+    /// Presentation behind the item; absent for media, headers and placeholders
+    #[serde(default)]
+    pub presentation_info: Option<PresentationInfo>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct PresentationInfo {
+    pub presentation_uuid: String,
+}
+
+impl PlaylistItem {
+    /// Length of a media/audio item, when ProPresenter reports one
+    pub fn media_length(&self) -> Option<Duration> {
+        self.duration
+            .filter(|secs| *secs > 0.0)
+            .map(|secs| Duration::from_ms((secs * MS_PER_SECOND as f64).round() as u64))
+    }
+
+    /// UUID of the presentation itself, which stays the same across playlists
+    pub fn presentation_uuid(&self) -> Option<&str> {
+        self.presentation_info.as_ref().map(|info| info.presentation_uuid.as_str())
+    }
+}
+
+/// Accept a plain number of seconds and ignore anything else, so an unexpected
+/// shape doesn't fail the whole playlist
+fn seconds_if_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    Ok(Option::<serde_json::Value>::deserialize(deserializer)?.and_then(|v| v.as_f64()))
 }
 
 #[derive(Debug, Deserialize, PartialEq, Clone)]
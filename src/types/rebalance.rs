@@ -0,0 +1,178 @@
+use super::formatter::fit_durations;
+use super::ontime::{OntimeEntry, OntimeRundown};
+use super::time::Duration;
+
+/// Where the show is against the schedule, from Ontime's `/api/poll`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeOffset {
+    /// Ontime's offset in ms. Negative means running behind: Ontime expects
+    /// to end at `plannedEnd - offset`.
+    pub offset_ms: i64,
+    pub current_event_id: Option<String>,
+}
+
+impl RuntimeOffset {
+    /// Read the poll payload. Newer servers report `offset.absolute`, older
+    /// ones a plain `runtime.offset`.
+    pub fn from_poll(json: &serde_json::Value) -> Option<Self> {
+        let payload = json.get("payload").unwrap_or(json);
+        let offset_ms = payload
+            .pointer("/offset/absolute")
+            .or_else(|| payload.pointer("/runtime/offset"))
+            .or_else(|| payload.pointer("/rundown/offset"))
+            .and_then(|v| v.as_f64())?;
+        let current_event_id = payload
+            .pointer("/eventNow/id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        Some(Self { offset_ms: offset_ms as i64, current_event_id })
+    }
+
+    /// How far behind schedule the show is, if it is behind
+    pub fn behind(&self) -> Option<Duration> {
+        (self.offset_ms < 0).then(|| Duration::from_ms(self.offset_ms.unsigned_abs()))
+    }
+}
+
+/// Proposed new duration for one remaining event
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrimProposal {
+    pub event_id: String,
+    pub title: String,
+    pub current: Duration,
+    pub proposed: Duration,
+}
+
+impl OntimeEntry {
+    /// Events set to keep their end time act as anchors the show has to meet
+    pub fn is_locked_end(&self) -> bool {
+        self._extra.get("timeStrategy").and_then(|v| v.as_str()) == Some("lock-end")
+    }
+
+    fn is_skipped(&self) -> bool {
+        self._extra.get("skip").and_then(|v| v.as_bool()).unwrap_or(false)
+    }
+}
+
+/// Spread `behind` across the events between the current one and the next
+/// locked anchor, trimming each in proportion to its length
+pub fn propose_trims(
+    rundown: &OntimeRundown,
+    current_event_id: Option<&str>,
+    behind: Duration,
+) -> Result<(String, Vec<TrimProposal>), String> {
+    let events: Vec<&OntimeEntry> = rundown
+        .flat_order
+        .iter()
+        .filter_map(|id| rundown.entries.get(id))
+        .filter(|e| e.entry_type == "event" && !e.is_skipped())
+        .collect();
+    let start = match current_event_id {
+        Some(id) => events.iter().position(|e| e.id == id).map(|p| p + 1).ok_or("The running event isn't in the rundown")?,
+        None => 0,
+    };
+    let remaining = &events[start..];
+    let anchor = remaining
+        .iter()
+        .position(|e| e.is_locked_end())
+        .ok_or("No locked-end event ahead to rebalance towards")?;
+    let flexible = &remaining[..anchor];
+    if flexible.is_empty() {
+        return Err(format!("Nothing to trim before {}", remaining[anchor].title));
+    }
+
+    let total: u64 = flexible.iter().map(|e| e.duration).sum();
+    if behind.as_ms() >= total {
+        return Err(format!(
+            "Running {} behind, but only {} is left before {}",
+            behind,
+            Duration::from_ms(total),
+            remaining[anchor].title
+        ));
+    }
+    let inputs: Vec<(Duration, bool)> = flexible.iter().map(|e| (Duration::from_ms(e.duration), false)).collect();
+    let proposed = fit_durations(&inputs, Duration::from_ms(total - behind.as_ms()))?;
+    let trims = flexible
+        .iter()
+        .zip(proposed)
+        .map(|(event, proposed)| TrimProposal {
+            event_id: event.id.clone(),
+            title: event.title.clone(),
+            current: Duration::from_ms(event.duration),
+            proposed,
+        })
+        .collect();
+    Ok((remaining[anchor].title.clone(), trims))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: u64 = 60_000;
+
+    fn rundown_of(events: &[(&str, u64, bool)]) -> OntimeRundown {
+        let entries: serde_json::Map<String, serde_json::Value> = events
+            .iter()
+            .map(|(id, minutes, locked)| {
+                let strategy = if *locked { "lock-end" } else { "lock-duration" };
+                let entry = serde_json::json!({
+                    "id": id,
+                    "type": "event",
+                    "title": id.to_uppercase(),
+                    "duration": minutes * MIN,
+                    "timeStrategy": strategy,
+                });
+                (id.to_string(), entry)
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "id": "default",
+            "title": "Sunday",
+            "flatOrder": events.iter().map(|(id, _, _)| *id).collect::<Vec<_>>(),
+            "entries": entries,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn negative_offset_is_behind() {
+        let poll = serde_json::json!({ "payload": { "offset": { "absolute": -90_000 }, "eventNow": { "id": "a" } } });
+        let offset = RuntimeOffset::from_poll(&poll).unwrap();
+        assert_eq!(offset.behind(), Some(Duration::from_ms(90_000)));
+        assert_eq!(offset.current_event_id.as_deref(), Some("a"));
+
+        let ahead = RuntimeOffset::from_poll(&serde_json::json!({ "runtime": { "offset": 30_000 } })).unwrap();
+        assert_eq!(ahead.behind(), None);
+    }
+
+    #[test]
+    fn trims_events_before_the_anchor() {
+        let rundown = rundown_of(&[("a", 5, false), ("b", 10, false), ("c", 20, false), ("d", 30, true), ("e", 5, false)]);
+        let (anchor, trims) = propose_trims(&rundown, Some("a"), Duration::from_ms(6 * MIN)).unwrap();
+        assert_eq!(anchor, "D");
+        let ids: Vec<&str> = trims.iter().map(|t| t.event_id.as_str()).collect();
+        assert_eq!(ids, ["b", "c"]);
+        // 30 minutes left, trimmed to 24 in proportion: 8 and 16
+        assert_eq!(trims[0].proposed, Duration::from_ms(8 * MIN));
+        assert_eq!(trims[1].proposed, Duration::from_ms(16 * MIN));
+    }
+
+    #[test]
+    fn needs_a_locked_anchor_ahead() {
+        let rundown = rundown_of(&[("a", 5, false), ("b", 10, false), ("c", 20, false)]);
+        let result = propose_trims(&rundown, Some("a"), Duration::from_ms(MIN));
+        assert_eq!(result, Err("No locked-end event ahead to rebalance towards".to_string()));
+        // The anchor already passed
+        let rundown = rundown_of(&[("a", 5, true), ("b", 10, false)]);
+        assert!(propose_trims(&rundown, Some("a"), Duration::from_ms(MIN)).is_err());
+    }
+
+    #[test]
+    fn cannot_trim_more_than_is_left() {
+        let rundown = rundown_of(&[("a", 5, false), ("b", 10, false), ("c", 20, true)]);
+        assert!(propose_trims(&rundown, Some("a"), Duration::from_ms(10 * MIN)).is_err());
+        assert!(propose_trims(&rundown, Some("b"), Duration::from_ms(MIN)).is_err());
+        assert!(propose_trims(&rundown, Some("missing"), Duration::from_ms(MIN)).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use super::formatter::{EntryPath, FormatterItem};
+use super::template::ServiceTemplate;
+use super::time::{Duration, MS_PER_SECOND};
+
+/// Where a rehearsal reads what is currently live from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RehearsalSource {
+    /// The active item of ProPresenter's active playlist
+    ProPresenter,
+    /// The running event in Ontime
+    Ontime,
+}
+
+impl RehearsalSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            RehearsalSource::ProPresenter => "ProPresenter",
+            RehearsalSource::Ontime => "Ontime",
+        }
+    }
+}
+
+/// The item a source reports as live
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveItem {
+    /// Playlist item UUID for ProPresenter, event id for Ontime
+    pub key: String,
+    pub name: String,
+    /// Only ProPresenter knows which presentation is behind the item
+    pub presentation_uuid: Option<String>,
+}
+
+impl LiveItem {
+    /// Read ProPresenter's `/v1/playlist/active` response, plus
+    /// `/v1/presentation/active` for the presentation UUID when available
+    pub fn from_propresenter(playlist: &serde_json::Value, presentation: Option<&serde_json::Value>) -> Option<Self> {
+        let item = playlist.pointer("/presentation/item")?;
+        Some(Self {
+            key: item.get("uuid")?.as_str()?.to_string(),
+            name: item.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            presentation_uuid: presentation
+                .and_then(|p| p.pointer("/presentation/id/uuid"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        })
+    }
+
+    /// Read the running event from Ontime's `/api/poll` response
+    pub fn from_ontime_poll(json: &serde_json::Value) -> Option<Self> {
+        let payload = json.get("payload").unwrap_or(json);
+        let event = payload.get("eventNow")?;
+        Some(Self {
+            key: event.get("id")?.as_str()?.to_string(),
+            name: event.get("title").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            presentation_uuid: None,
+        })
+    }
+}
+
+/// One stretch of an item being live during a rehearsal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RehearsalTake {
+    pub key: String,
+    pub name: String,
+    #[serde(default)]
+    pub presentation_uuid: Option<String>,
+    pub duration: Duration,
+}
+
+/// Turns a stream of "what's live now" observations into per-item durations
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RehearsalRecorder {
+    /// Item currently live and when it went live (ms since the epoch)
+    current: Option<(LiveItem, u64)>,
+    /// One per visit, in the order items went live. An item used twice in the
+    /// service (a repeated song or prayer) gets a take for each time it was live.
+    pub takes: Vec<RehearsalTake>,
+}
+
+impl RehearsalRecorder {
+    pub fn current(&self) -> Option<&LiveItem> {
+        self.current.as_ref().map(|(item, _)| item)
+    }
+
+    /// Record what is live at `now_ms`, closing the previous item if it changed
+    pub fn observe(&mut self, live: Option<LiveItem>, now_ms: u64) {
+        if self.current() == live.as_ref() {
+            return;
+        }
+        self.finish(now_ms);
+        self.current = live.map(|item| (item, now_ms));
+    }
+
+    /// Close the item that is live at `now_ms`
+    pub fn finish(&mut self, now_ms: u64) {
+        let Some((item, started)) = self.current.take() else { return };
+        self.takes.push(RehearsalTake {
+            key: item.key,
+            name: item.name,
+            presentation_uuid: item.presentation_uuid,
+            duration: Duration::from_ms(now_ms.saturating_sub(started)),
+        });
+    }
+}
+
+/// Rehearsed durations rounded to whole seconds, as entered by hand
+fn rounded(duration: Duration) -> Duration {
+    Duration::from_ms((duration.as_ms() + MS_PER_SECOND / 2) / MS_PER_SECOND * MS_PER_SECOND)
+}
+
+/// Write rehearsed durations into matching formatter entries: by playlist item
+/// UUID first, then by name. Entries take the earliest unused matching take in
+/// plan order, so repeated items each get their own visit. Returns how many entries changed.
+pub fn apply_takes_to_items(items: &mut [FormatterItem], takes: &[RehearsalTake]) -> usize {
+    let mut used = vec![false; takes.len()];
+    let mut updated = 0;
+    for path in EntryPath::all(items) {
+        let Some(entry) = path.entry_mut(items) else { continue };
+        let unused = |matches: &dyn Fn(&RehearsalTake) -> bool| {
+            (0..takes.len()).find(|&i| !used[i] && matches(&takes[i]))
+        };
+        let found = unused(&|t| !entry.item_id.is_empty() && t.key == entry.item_id)
+            .or_else(|| unused(&|t| t.name.eq_ignore_ascii_case(&entry.name)));
+        if let Some(i) = found {
+            used[i] = true;
+            entry.duration = rounded(takes[i].duration);
+            updated += 1;
+        }
+    }
+    updated
+}
+
+/// Write rehearsed durations into the template's slots, matching the slot's
+/// match text or label against the item name. Returns how many slots changed.
+pub fn apply_takes_to_template(template: &mut ServiceTemplate, takes: &[RehearsalTake]) -> usize {
+    let mut updated = 0;
+    for slot in template.groups.iter_mut().flat_map(|g| g.slots.iter_mut()) {
+        let needle = match slot.match_text.trim() {
+            "" => slot.label.trim().to_lowercase(),
+            text => text.to_lowercase(),
+        };
+        if needle.is_empty() {
+            continue;
+        }
+        if let Some(take) = takes.iter().find(|t| t.name.to_lowercase().contains(&needle)) {
+            slot.duration = rounded(take.duration);
+            updated += 1;
+        }
+    }
+    updated
+}
@@ -0,0 +1,248 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use super::ontime::OntimeRundown;
+use super::time::{Duration, MS_PER_DAY};
+use crate::utils::{escape_html, format_ms_to_duration};
+
+/// When one event actually ran, from Ontime's `/data/report` (ms from midnight)
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct EventActuals {
+    #[serde(rename = "startedAt", default)]
+    pub started_at: Option<u64>,
+    #[serde(rename = "endedAt", default)]
+    pub ended_at: Option<u64>,
+}
+
+/// Ontime's session report: actual start/end per event id
+pub type OntimeReport = HashMap<String, EventActuals>;
+
+/// Difference between two times of day, taking the shorter way round midnight
+fn clock_diff(actual: u64, planned: u64) -> i64 {
+    let day = MS_PER_DAY as i64;
+    let diff = (actual as i64 - planned as i64).rem_euclid(day);
+    if diff > day / 2 { diff - day } else { diff }
+}
+
+/// Signed duration, e.g. "+00:01:30" for late/over and "-00:00:20" for early/under
+pub fn format_signed_ms(ms: i64) -> String {
+    let sign = if ms < 0 { "-" } else { "+" };
+    format!("{}{}", sign, format_ms_to_duration(ms.unsigned_abs()))
+}
+
+/// Planned vs actual for one event
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventReportRow {
+    pub title: String,
+    pub cue: String,
+    /// Title of the group the event sits in
+    pub group: Option<String>,
+    pub planned_start: u64,
+    pub planned_duration: Duration,
+    /// None when the event never ran
+    pub actual_start: Option<u64>,
+    pub actual_duration: Option<Duration>,
+}
+
+impl EventReportRow {
+    /// Positive when the event started late
+    pub fn start_drift(&self) -> Option<i64> {
+        self.actual_start.map(|start| clock_diff(start, self.planned_start))
+    }
+
+    /// Positive when the event overran
+    pub fn duration_variance(&self) -> Option<i64> {
+        self.actual_duration
+            .map(|actual| actual.as_ms() as i64 - self.planned_duration.as_ms() as i64)
+    }
+}
+
+/// Planned vs actual for the events of one group that ran
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupReportRow {
+    pub title: String,
+    pub planned_duration: Duration,
+    pub actual_duration: Duration,
+    pub events_run: usize,
+}
+
+impl GroupReportRow {
+    pub fn variance(&self) -> i64 {
+        self.actual_duration.as_ms() as i64 - self.planned_duration.as_ms() as i64
+    }
+}
+
+/// Post-show comparison of the pushed rundown with what actually happened
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowReport {
+    pub rundown_title: String,
+    pub generated_at: String,
+    pub events: Vec<EventReportRow>,
+    pub groups: Vec<GroupReportRow>,
+}
+
+impl ShowReport {
+    pub fn build(rundown: &OntimeRundown, report: &OntimeReport) -> Self {
+        let group_title = |parent: &Option<String>| {
+            parent
+                .as_ref()
+                .and_then(|id| rundown.entries.get(id))
+                .map(|group| group.title.clone())
+        };
+
+        let events: Vec<EventReportRow> = rundown
+            .flat_order
+            .iter()
+            .filter_map(|id| rundown.entries.get(id))
+            .filter(|e| e.entry_type == "event")
+            .map(|event| {
+                let actuals = report.get(&event.id).copied().unwrap_or_default();
+                EventReportRow {
+                    title: event.title.clone(),
+                    cue: event.cue.clone(),
+                    group: group_title(&event.parent),
+                    planned_start: event.time_start,
+                    planned_duration: Duration::from_ms(event.duration),
+                    actual_start: actuals.started_at,
+                    actual_duration: match (actuals.started_at, actuals.ended_at) {
+                        (Some(start), Some(end)) => Some(Duration::from_ms(clock_diff(end, start).max(0) as u64)),
+                        _ => None,
+                    },
+                }
+            })
+            .collect();
+
+        // Only events that ran count, so skipped items don't show up as time saved
+        let mut groups: Vec<GroupReportRow> = Vec::new();
+        for event in &events {
+            let (Some(title), Some(actual)) = (&event.group, event.actual_duration) else { continue };
+            let row = match groups.iter_mut().position(|g| &g.title == title) {
+                Some(idx) => &mut groups[idx],
+                None => {
+                    groups.push(GroupReportRow {
+                        title: title.clone(),
+                        planned_duration: Duration::default(),
+                        actual_duration: Duration::default(),
+                        events_run: 0,
+                    });
+                    groups.last_mut().unwrap()
+                }
+            };
+            row.planned_duration = row.planned_duration + event.planned_duration;
+            row.actual_duration = row.actual_duration + actual;
+            row.events_run += 1;
+        }
+
+        Self {
+            rundown_title: rundown.title.clone(),
+            generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+            events,
+            groups,
+        }
+    }
+
+    /// How late the last event that ran finished against its planned end
+    pub fn total_overrun(&self) -> Option<i64> {
+        self.events
+            .iter()
+            .rev()
+            .find_map(|e| Some((e, e.actual_start? + e.actual_duration?.as_ms())))
+            .map(|(e, actual_end)| clock_diff(actual_end, e.planned_start + e.planned_duration.as_ms()))
+    }
+
+    pub fn events_run(&self) -> usize {
+        self.events.iter().filter(|e| e.actual_start.is_some()).count()
+    }
+
+    pub fn to_csv(&self) -> Result<String, String> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        let optional_time = |ms: Option<u64>| ms.map(format_ms_to_duration).unwrap_or_default();
+        let optional_signed = |ms: Option<i64>| ms.map(format_signed_ms).unwrap_or_default();
+        writer
+            .write_record([
+                "Group", "Cue", "Title", "Planned start", "Actual start", "Start drift",
+                "Planned duration", "Actual duration", "Duration variance",
+            ])
+            .map_err(|e| e.to_string())?;
+        for event in &self.events {
+            writer
+                .write_record([
+                    event.group.clone().unwrap_or_default(),
+                    event.cue.clone(),
+                    event.title.clone(),
+                    format_ms_to_duration(event.planned_start),
+                    optional_time(event.actual_start),
+                    optional_signed(event.start_drift()),
+                    event.planned_duration.to_string(),
+                    optional_time(event.actual_duration.map(|d| d.as_ms())),
+                    optional_signed(event.duration_variance()),
+                ])
+                .map_err(|e| e.to_string())?;
+        }
+        let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+        String::from_utf8(bytes).map_err(|e| e.to_string())
+    }
+
+    pub fn to_html(&self) -> String {
+        let variance_class = |ms: i64| match ms {
+            ms if ms > 0 => "over",
+            ms if ms < 0 => "under",
+            _ => "",
+        };
+        let signed_cell = |ms: Option<i64>| match ms {
+            Some(ms) => format!("<td class=\"{}\">{}</td>", variance_class(ms), format_signed_ms(ms)),
+            None => "<td class=\"muted\">—</td>".to_string(),
+        };
+
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">");
+        html.push_str(&format!("<title>Show report - {}</title>", escape_html(&self.rundown_title)));
+        html.push_str(
+            "<style>body{font-family:sans-serif;margin:2em;color:#18181b}table{border-collapse:collapse;width:100%;margin-bottom:2em}\
+             th,td{border-bottom:1px solid #e4e4e7;padding:4px 8px;text-align:left;font-variant-numeric:tabular-nums}\
+             th{background:#f4f4f5}.over{color:#dc2626}.under{color:#059669}.muted{color:#a1a1aa}</style></head><body>\n",
+        );
+        html.push_str(&format!("<h1>{}</h1>\n", escape_html(&self.rundown_title)));
+        html.push_str(&format!("<p>Generated {} · {} of {} events ran", self.generated_at, self.events_run(), self.events.len()));
+        if let Some(overrun) = self.total_overrun() {
+            html.push_str(&format!(
+                " · Total overrun <strong class=\"{}\">{}</strong>",
+                variance_class(overrun),
+                format_signed_ms(overrun)
+            ));
+        }
+        html.push_str("</p>\n");
+
+        if !self.groups.is_empty() {
+            html.push_str("<h2>Groups</h2>\n<table><tr><th>Group</th><th>Events run</th><th>Planned</th><th>Actual</th><th>Variance</th></tr>\n");
+            for group in &self.groups {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td>{}</tr>\n",
+                    escape_html(&group.title),
+                    group.events_run,
+                    group.planned_duration,
+                    group.actual_duration,
+                    signed_cell(Some(group.variance())),
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("<h2>Events</h2>\n<table><tr><th>Group</th><th>Cue</th><th>Title</th><th>Planned start</th><th>Actual start</th><th>Drift</th><th>Planned</th><th>Actual</th><th>Variance</th></tr>\n");
+        for event in &self.events {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>{}<td>{}</td><td>{}</td>{}</tr>\n",
+                escape_html(event.group.as_deref().unwrap_or_default()),
+                escape_html(&event.cue),
+                escape_html(&event.title),
+                format_ms_to_duration(event.planned_start),
+                event.actual_start.map(format_ms_to_duration).unwrap_or_else(|| "—".to_string()),
+                signed_cell(event.start_drift()),
+                event.planned_duration,
+                event.actual_duration.map(|d| d.to_string()).unwrap_or_else(|| "—".to_string()),
+                signed_cell(event.duration_variance()),
+            ));
+        }
+        html.push_str("</table>\n</body></html>\n");
+        html
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use super::formatter::TimedEntry;
+use super::propresenter::PlaylistItem;
+use super::time::Duration;
+
+/// How a rule's pattern is compared against a playlist item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleMatchKind {
+    /// Regular expression on the item name
+    Regex,
+    /// Case-insensitive substring of the item name
+    Contains,
+    /// Exact (case-insensitive) ProPresenter item type, e.g. `media`
+    ItemType,
+}
+
+impl RuleMatchKind {
+    pub const ALL: [RuleMatchKind; 3] = [RuleMatchKind::Regex, RuleMatchKind::Contains, RuleMatchKind::ItemType];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RuleMatchKind::Regex => "Name regex",
+            RuleMatchKind::Contains => "Name contains",
+            RuleMatchKind::ItemType => "Item type",
+        }
+    }
+}
+
+/// Name-pattern rule applied when a playlist item becomes a timed entry.
+/// Empty / `None` actions leave the entry's value alone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NameRule {
+    pub kind: RuleMatchKind,
+    pub pattern: String,
+    #[serde(default)]
+    pub duration: Option<Duration>,
+    /// Use the media item's own length (falls back to `duration` when ProPresenter doesn't report one)
+    #[serde(default)]
+    pub use_media_length: bool,
+    #[serde(default)]
+    pub count_to_end: Option<bool>,
+    #[serde(default)]
+    pub color: String,
+    /// Name of the group matching items are placed in (created if it doesn't exist)
+    #[serde(default)]
+    pub group: String,
+}
+
+impl NameRule {
+    pub fn new(kind: RuleMatchKind) -> Self {
+        Self {
+            kind,
+            pattern: String::new(),
+            duration: None,
+            use_media_length: false,
+            count_to_end: None,
+            color: String::new(),
+            group: String::new(),
+        }
+    }
+
+    /// Why the pattern can't be used, if it can't
+    pub fn pattern_error(&self) -> Option<String> {
+        if self.pattern.trim().is_empty() {
+            return Some("Pattern is empty".to_string());
+        }
+        match self.kind {
+            RuleMatchKind::Regex => regex::Regex::new(&self.pattern).err().map(|e| e.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Whether the rule picks up `item`. Regex rules match through `regex`, compiled
+    /// once by `RuleSet`; without one (an invalid pattern) they match nothing.
+    fn matches(&self, regex: Option<&regex::Regex>, item: &PlaylistItem) -> bool {
+        let pattern = self.pattern.trim();
+        if pattern.is_empty() {
+            return false;
+        }
+        match self.kind {
+            RuleMatchKind::Regex => regex.is_some_and(|re| re.is_match(&item.id.name)),
+            RuleMatchKind::Contains => item.id.name.to_lowercase().contains(&pattern.to_lowercase()),
+            RuleMatchKind::ItemType => item.item_type.eq_ignore_ascii_case(pattern),
+        }
+    }
+
+    /// Short description of what the rule does, for previews
+    pub fn summary(&self) -> String {
+        let mut actions = Vec::new();
+        if self.use_media_length {
+            actions.push("media length".to_string());
+        } else if let Some(duration) = self.duration {
+            actions.push(duration.to_string());
+        }
+        if let Some(cte) = self.count_to_end {
+            actions.push(if cte { "count to end" } else { "count down" }.to_string());
+        }
+        if !self.color.is_empty() {
+            actions.push(self.color.clone());
+        }
+        if !self.group.is_empty() {
+            actions.push(format!("→ {}", self.group));
+        }
+        format!("{} \"{}\": {}", self.kind.label(), self.pattern, actions.join(", "))
+    }
+}
+
+/// Rules with their regex patterns compiled, built once per change to the rule list
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<NameRule>,
+    regexes: Vec<Option<regex::Regex>>,
+}
+
+impl RuleSet {
+    pub fn new(rules: &[NameRule]) -> Self {
+        let regexes = rules
+            .iter()
+            .map(|rule| match rule.kind {
+                RuleMatchKind::Regex => regex::Regex::new(rule.pattern.trim()).ok(),
+                _ => None,
+            })
+            .collect();
+        Self { rules: rules.to_vec(), regexes }
+    }
+}
+
+// The compiled regexes follow from the rules, so they are all there is to compare
+impl PartialEq for RuleSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.rules == other.rules
+    }
+}
+
+/// First rule (by position) that matches the item
+pub fn matching_rule<'a>(rules: &'a RuleSet, item: &PlaylistItem) -> Option<(usize, &'a NameRule)> {
+    rules
+        .rules
+        .iter()
+        .zip(&rules.regexes)
+        .enumerate()
+        .find(|(_, (rule, regex))| rule.matches(regex.as_ref(), item))
+        .map(|(idx, (rule, _))| (idx, rule))
+}
+
+/// Apply the first matching rule to a fresh entry. Returns the index of the
+/// rule that matched and the group it routes the entry to, if any.
+pub fn apply_rules(
+    rules: &RuleSet,
+    item: &PlaylistItem,
+    entry: &mut TimedEntry,
+) -> Option<(usize, Option<String>)> {
+    let (idx, rule) = matching_rule(rules, item)?;
+    if let Some(length) = item.media_length().filter(|_| rule.use_media_length) {
+        entry.duration = length;
+    } else if let Some(duration) = rule.duration {
+        entry.duration = duration;
+    }
+    if let Some(cte) = rule.count_to_end {
+        entry.count_to_end = cte;
+    }
+    if !rule.color.is_empty() {
+        entry.color = rule.color.clone();
+    }
+    let group = (!rule.group.trim().is_empty()).then(|| rule.group.trim().to_string());
+    Some((idx, group))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Dictionary;
+
+    fn item(name: &str, item_type: &str) -> PlaylistItem {
+        PlaylistItem {
+            id: Dictionary { uuid: String::new(), name: name.to_string(), index: 0 },
+            item_type: item_type.to_string(),
+            duration: None,
+            presentation_info: None,
+        }
+    }
+
+    fn rule(kind: RuleMatchKind, pattern: &str) -> NameRule {
+        NameRule { pattern: pattern.to_string(), ..NameRule::new(kind) }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = RuleSet::new(&[
+            rule(RuleMatchKind::Regex, "(unclosed"),
+            rule(RuleMatchKind::Regex, r"^Song \d+$"),
+            rule(RuleMatchKind::Contains, "song"),
+            rule(RuleMatchKind::ItemType, "Media"),
+        ]);
+        let idx = |name: &str, kind: &str| matching_rule(&rules, &item(name, kind)).map(|(idx, _)| idx);
+        assert_eq!(idx("Song 12", "presentation"), Some(1));
+        assert_eq!(idx("Closing song", "presentation"), Some(2));
+        assert_eq!(idx("Countdown", "media"), Some(3));
+        assert_eq!(idx("(unclosed", "presentation"), None);
+    }
+}
@@ -0,0 +1,337 @@
+use chrono::{Datelike, NaiveDate};
+use printpdf::{BuiltinFont, Color, Mm, PdfDocument, PdfLayerReference, Rect, Rgb};
+use super::export::{ScheduleRow, ScheduleRowKind};
+use super::time::{Duration, TimeOfDay};
+use crate::utils::escape_html;
+
+/// The plan laid out for paper: service name and date on top, rows grouped under coloured headers
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSheet {
+    pub service_name: String,
+    pub date: String,
+    pub rows: Vec<ScheduleRow>,
+}
+
+/// A run of consecutive rows sharing a group (or no group)
+struct Section<'a> {
+    group: Option<&'a (String, String)>,
+    rows: &'a [ScheduleRow],
+}
+
+impl Section<'_> {
+    fn total(&self) -> Duration {
+        self.rows.iter().fold(Duration::default(), |total, row| total + row.duration)
+    }
+}
+
+/// Date a plan is usually printed for: `today` if it's a Sunday, otherwise the coming Sunday
+pub fn next_service_day(today: NaiveDate) -> NaiveDate {
+    let days_ahead = (7 - today.weekday().num_days_from_sunday()) % 7;
+    today + chrono::Days::new(u64::from(days_ahead))
+}
+
+/// Date as printed in the run sheet header, e.g. "Sunday 19 October 2026"
+pub fn run_sheet_date(date: NaiveDate) -> String {
+    date.format("%A %-d %B %Y").to_string()
+}
+
+fn optional_time(time: Option<TimeOfDay>) -> String {
+    time.map(|t| t.to_string()).unwrap_or_else(|| "—".to_string())
+}
+
+/// `#RRGGBB` as 0.0-1.0 components
+fn hex_rgb(color: &str) -> Option<(f32, f32, f32)> {
+    let hex = color.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok().map(|v| v as f32 / 255.0);
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Cut `text` to `max` characters, marking the cut
+fn clip(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}...", text.chars().take(max.saturating_sub(3)).collect::<String>())
+    }
+}
+
+const PRINT_CSS: &str = "\
+body{font-family:-apple-system,'Segoe UI',Helvetica,Arial,sans-serif;color:#18181b;margin:2em;font-size:11pt}\
+header{display:flex;justify-content:space-between;align-items:baseline;border-bottom:2px solid #18181b;margin-bottom:1em}\
+h1{margin:0 0 .2em;font-size:20pt}.date{color:#52525b}\
+table{border-collapse:collapse;width:100%}\
+th{text-align:left;font-size:8pt;text-transform:uppercase;letter-spacing:.05em;color:#71717a;border-bottom:1px solid #a1a1aa;padding:4px 6px}\
+td{padding:5px 6px;border-bottom:1px solid #e4e4e7;vertical-align:top}\
+.time{font-family:'JetBrains Mono',Menlo,Consolas,monospace;white-space:nowrap}\
+.cue{font-weight:bold;white-space:nowrap}.note{color:#52525b;font-size:9pt;white-space:pre-wrap}\
+.group td{font-weight:bold;text-transform:uppercase;letter-spacing:.05em;border-bottom:none;padding-top:12px}\
+.delay td{font-style:italic;color:#b45309}.milestone td{font-weight:bold}\
+.swatch{display:inline-block;width:.7em;height:.7em;border-radius:2px;margin-right:.4em}\
+@page{size:A4;margin:12mm}\
+@media print{body{margin:0}thead{display:table-header-group}tr{break-inside:avoid}\
+*{-webkit-print-color-adjust:exact;print-color-adjust:exact}}";
+
+impl RunSheet {
+    fn sections(&self) -> Vec<Section<'_>> {
+        let mut sections = Vec::new();
+        let mut start = 0;
+        for idx in 1..=self.rows.len() {
+            if idx == self.rows.len() || self.rows[idx].group != self.rows[start].group {
+                sections.push(Section { group: self.rows[start].group.as_ref(), rows: &self.rows[start..idx] });
+                start = idx;
+            }
+        }
+        sections
+    }
+
+    /// Self-contained HTML page with print styles, ready for the browser's print dialog
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">");
+        html.push_str(&format!("<title>Run sheet - {}</title>", escape_html(&self.service_name)));
+        html.push_str(&format!("<style>{}</style></head><body>\n", PRINT_CSS));
+        html.push_str(&format!(
+            "<header><h1>{}</h1><span class=\"date\">{}</span></header>\n",
+            escape_html(&self.service_name),
+            escape_html(&self.date)
+        ));
+        html.push_str("<table><thead><tr><th>Cue</th><th>Start</th><th>End</th><th>Dur</th><th>Item</th></tr></thead>\n");
+        for section in self.sections() {
+            html.push_str("<tbody>\n");
+            if let Some((name, color)) = section.group {
+                // A light tint of the group colour, which is only known for `#RRGGBB`
+                let tint = hex_rgb(color)
+                    .map(|(r, g, b)| {
+                        let channel = |c: f32| (c * 255.0).round() as u8;
+                        format!(";background:rgba({},{},{},0.13)", channel(r), channel(g), channel(b))
+                    })
+                    .unwrap_or_default();
+                html.push_str(&format!(
+                    "<tr class=\"group\"><td colspan=\"5\" style=\"border-left:6px solid {}{}\">{} <span class=\"time\">· {}</span></td></tr>\n",
+                    escape_html(color),
+                    tint,
+                    escape_html(name),
+                    section.total()
+                ));
+            }
+            for row in section.rows {
+                let class = match row.kind {
+                    ScheduleRowKind::Event => "",
+                    ScheduleRowKind::Delay => " class=\"delay\"",
+                    ScheduleRowKind::Milestone => " class=\"milestone\"",
+                };
+                let swatch = if row.color.is_empty() {
+                    String::new()
+                } else {
+                    format!("<span class=\"swatch\" style=\"background:{}\"></span>", escape_html(&row.color))
+                };
+                let note = if row.note.is_empty() {
+                    String::new()
+                } else {
+                    format!("<div class=\"note\">{}</div>", escape_html(&row.note))
+                };
+                html.push_str(&format!(
+                    "<tr{}><td class=\"cue\">{}</td><td class=\"time\">{}</td><td class=\"time\">{}</td><td class=\"time\">{}</td><td>{}{}{}</td></tr>\n",
+                    class,
+                    escape_html(&row.cue),
+                    optional_time(row.start),
+                    optional_time(row.end),
+                    if row.kind == ScheduleRowKind::Milestone { String::new() } else { row.duration.to_string() },
+                    swatch,
+                    escape_html(&row.title),
+                    note
+                ));
+            }
+            html.push_str("</tbody>\n");
+        }
+        html.push_str("</table>\n</body></html>\n");
+        html
+    }
+
+    /// A4 PDF of the same layout, drawn with the built-in Helvetica fonts
+    pub fn to_pdf(&self) -> Result<Vec<u8>, String> {
+        const PAGE_WIDTH: f32 = 210.0;
+        const PAGE_HEIGHT: f32 = 297.0;
+        const MARGIN: f32 = 15.0;
+        const ROW_HEIGHT: f32 = 6.0;
+        const NOTE_HEIGHT: f32 = 4.5;
+        // Left edge of each column: cue, start, end, duration, item
+        const COLUMNS: [f32; 5] = [MARGIN, 32.0, 54.0, 76.0, 98.0];
+
+        let (doc, page, layer) = PdfDocument::new(
+            format!("Run sheet - {}", self.service_name),
+            Mm(PAGE_WIDTH),
+            Mm(PAGE_HEIGHT),
+            "Run sheet",
+        );
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| e.to_string())?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(|e| e.to_string())?;
+        let black = || Color::Rgb(Rgb::new(0.09, 0.09, 0.11, None));
+        let grey = || Color::Rgb(Rgb::new(0.44, 0.44, 0.48, None));
+
+        let column_headers = |layer: &PdfLayerReference, y: f32| {
+            layer.set_fill_color(grey());
+            for (x, label) in COLUMNS.iter().zip(["CUE", "START", "END", "DUR", "ITEM"]) {
+                layer.use_text(label, 7.0, Mm(*x), Mm(y), &bold);
+            }
+            layer.set_fill_color(black());
+        };
+
+        let mut layer = doc.get_page(page).get_layer(layer);
+        layer.set_fill_color(black());
+        layer.use_text(clip(&self.service_name, 50), 18.0, Mm(MARGIN), Mm(PAGE_HEIGHT - MARGIN - 6.0), &bold);
+        layer.set_fill_color(grey());
+        layer.use_text(self.date.clone(), 10.0, Mm(MARGIN), Mm(PAGE_HEIGHT - MARGIN - 12.0), &regular);
+        let mut y = PAGE_HEIGHT - MARGIN - 22.0;
+        column_headers(&layer, y);
+        y -= ROW_HEIGHT;
+
+        // Start a new page when the next block of `height` wouldn't fit
+        let ensure_room = |layer: &mut PdfLayerReference, y: &mut f32, height: f32| {
+            if *y - height < MARGIN {
+                let (page, new_layer) = doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Run sheet");
+                *layer = doc.get_page(page).get_layer(new_layer);
+                *y = PAGE_HEIGHT - MARGIN;
+                column_headers(layer, *y);
+                *y -= ROW_HEIGHT;
+            }
+        };
+
+        let write_row = |layer: &PdfLayerReference, row: &ScheduleRow, y: f32| {
+            let font = if row.kind == ScheduleRowKind::Milestone { &bold } else { &regular };
+            if row.kind == ScheduleRowKind::Delay {
+                layer.set_fill_color(Color::Rgb(Rgb::new(0.71, 0.33, 0.04, None)));
+            }
+            layer.use_text(clip(&row.cue, 8), 9.0, Mm(COLUMNS[0]), Mm(y), &bold);
+            layer.use_text(optional_time(row.start), 9.0, Mm(COLUMNS[1]), Mm(y), font);
+            layer.use_text(optional_time(row.end), 9.0, Mm(COLUMNS[2]), Mm(y), font);
+            if row.kind != ScheduleRowKind::Milestone {
+                layer.use_text(row.duration.to_string(), 9.0, Mm(COLUMNS[3]), Mm(y), font);
+            }
+            let mut title_x = COLUMNS[4];
+            if let Some((r, g, b)) = hex_rgb(&row.color) {
+                layer.set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
+                layer.add_rect(Rect::new(Mm(title_x), Mm(y), Mm(title_x + 2.5), Mm(y + 2.5)));
+                title_x += 4.0;
+            }
+            layer.set_fill_color(black());
+            layer.use_text(clip(&row.title, 55), 9.0, Mm(title_x), Mm(y), font);
+            if !row.note.is_empty() {
+                layer.set_fill_color(grey());
+                let note = row.note.lines().next().unwrap_or_default();
+                layer.use_text(clip(note, 75), 7.5, Mm(title_x), Mm(y - NOTE_HEIGHT), &regular);
+                layer.set_fill_color(black());
+            }
+        };
+
+        for section in self.sections() {
+            if let Some((name, color)) = section.group {
+                ensure_room(&mut layer, &mut y, ROW_HEIGHT * 2.0);
+                y -= 2.0;
+                let (r, g, b) = hex_rgb(color).unwrap_or((0.47, 0.61, 0.91));
+                layer.set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
+                layer.add_rect(Rect::new(Mm(MARGIN), Mm(y - 1.5), Mm(MARGIN + 1.5), Mm(y + 4.0)));
+                layer.set_fill_color(black());
+                layer.use_text(
+                    format!("{}  ·  {}", clip(&name.to_uppercase(), 60), section.total()),
+                    10.0,
+                    Mm(MARGIN + 4.0),
+                    Mm(y),
+                    &bold,
+                );
+                y -= ROW_HEIGHT;
+            }
+            for row in section.rows {
+                let height = if row.note.is_empty() { ROW_HEIGHT } else { ROW_HEIGHT + NOTE_HEIGHT };
+                ensure_room(&mut layer, &mut y, height);
+                write_row(&layer, row, y);
+                y -= height;
+            }
+        }
+
+        doc.save_to_bytes().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{computed_schedule, FormatterItem, TimedEntry};
+
+    fn entry(name: &str) -> TimedEntry {
+        TimedEntry::manual(name.to_string(), Duration::from_ms(60_000), TimeOfDay::MIDNIGHT)
+    }
+
+    fn group(name: &str, color: &str, entries: Vec<TimedEntry>) -> FormatterItem {
+        FormatterItem::Group {
+            id: format!("id-{}", name),
+            name: name.to_string(),
+            color: color.to_string(),
+            entries,
+            collapsed: false,
+            target_duration: None,
+        }
+    }
+
+    fn sheet(items: &[FormatterItem]) -> RunSheet {
+        RunSheet {
+            service_name: "Sunday".to_string(),
+            date: "Sunday 18 October 2026".to_string(),
+            rows: computed_schedule(items),
+        }
+    }
+
+    #[test]
+    fn next_service_day_is_the_coming_sunday() {
+        let date = |d: u32| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        // 18 October 2026 is a Sunday
+        assert_eq!(next_service_day(date(18)), date(18));
+        assert_eq!(next_service_day(date(19)), date(25));
+        assert_eq!(next_service_day(date(24)), date(25));
+        let new_year = NaiveDate::from_ymd_opt(2027, 1, 3).unwrap();
+        assert_eq!(next_service_day(NaiveDate::from_ymd_opt(2026, 12, 29).unwrap()), new_year);
+        assert_eq!(run_sheet_date(date(18)), "Sunday 18 October 2026");
+    }
+
+    #[test]
+    fn sections_follow_runs_of_rows() {
+        let items = vec![
+            FormatterItem::Standalone(entry("Welcome")),
+            group("Worship", "#779BE7", vec![entry("Song 1"), entry("Song 2")]),
+            FormatterItem::Standalone(entry("Notices")),
+            FormatterItem::new_milestone("Offering"),
+            group("Worship", "#779BE7", vec![entry("Song 3")]),
+        ];
+        let sheet = sheet(&items);
+        let sections: Vec<(Option<&str>, usize)> = sheet
+            .sections()
+            .iter()
+            .map(|s| (s.group.map(|(name, _)| name.as_str()), s.rows.len()))
+            .collect();
+        assert_eq!(sections, [(None, 1), (Some("Worship"), 2), (None, 2), (Some("Worship"), 1)]);
+        assert_eq!(sheet.sections()[1].total(), Duration::from_ms(120_000));
+        assert!(RunSheet { rows: vec![], ..sheet }.sections().is_empty());
+    }
+
+    #[test]
+    fn html_escapes_text_and_tints_groups() {
+        let mut song = entry("<b>Rock & Roll</b>");
+        song.note = "Say \"hi\" & 'wave'".to_string();
+        song.color = "red\" onmouseover='x'".to_string();
+        let items = vec![
+            group("Praise", "#ff8000", vec![song]),
+            group("Named", "teal", vec![entry("Song")]),
+        ];
+        let html = sheet(&items).to_html();
+        assert!(html.contains("&lt;b&gt;Rock &amp; Roll&lt;/b&gt;"));
+        assert!(html.contains("Say &quot;hi&quot; &amp; &#39;wave&#39;"));
+        assert!(html.contains("background:red&quot; onmouseover=&#39;x&#39;"));
+        assert!(!html.contains("<b>"));
+        assert!(html.contains("border-left:6px solid #ff8000;background:rgba(255,128,0,0.13)"));
+        // Named colours get the border but no tint
+        assert!(html.contains("border-left:6px solid teal\""));
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use super::formatter::FormatterItem;
+use super::settings::AppSettings;
+
+/// Random token picked once per launch. Unlike the pid it can't be reused by a
+/// later run, so a journal carrying it was always written by this process.
+fn launch_id() -> &'static str {
+    static LAUNCH_ID: OnceLock<String> = OnceLock::new();
+    LAUNCH_ID.get_or_init(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// Autosaved copy of the formatter session, kept in the config directory so a
+/// crash or a route change doesn't lose the plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionJournal {
+    /// Launch that wrote the journal. A different id means the app was restarted;
+    /// journals from before the field existed read as empty and count as restarted.
+    #[serde(default)]
+    pub launch_id: String,
+    pub saved_at: String,
+    pub playlist_name: String,
+    /// Project file the session was opened from or saved to, if any
+    #[serde(default)]
+    pub project_path: Option<String>,
+    /// True when the items differ from what was last saved to `project_path`
+    pub unsaved: bool,
+    pub items: Vec<FormatterItem>,
+}
+
+impl SessionJournal {
+    pub fn new(
+        playlist_name: String,
+        project_path: Option<String>,
+        unsaved: bool,
+        items: Vec<FormatterItem>,
+    ) -> Self {
+        Self {
+            launch_id: launch_id().to_string(),
+            saved_at: chrono::Local::now().to_rfc3339(),
+            playlist_name,
+            project_path,
+            unsaved,
+            items,
+        }
+    }
+
+    fn journal_path() -> std::path::PathBuf {
+        AppSettings::config_dir().join("session.json")
+    }
+
+    pub fn load() -> Option<Self> {
+        let contents = std::fs::read_to_string(Self::journal_path()).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = Self::journal_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write to a temp file first so a crash mid-write can't corrupt the journal
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?)?;
+        std::fs::rename(&tmp, &path)
+    }
+
+    pub fn clear() -> std::io::Result<()> {
+        match std::fs::remove_file(Self::journal_path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Written by an earlier run of the app rather than this one
+    pub fn is_from_previous_run(&self) -> bool {
+        self.launch_id != launch_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal_from_this_launch_is_not_a_previous_run() {
+        let journal = SessionJournal::new("Sunday".into(), None, false, Vec::new());
+        assert!(!journal.is_from_previous_run());
+    }
+
+    #[test]
+    fn journal_from_another_launch_is_a_previous_run() {
+        let mut journal = SessionJournal::new("Sunday".into(), None, false, Vec::new());
+        journal.launch_id = uuid::Uuid::new_v4().to_string();
+        assert!(journal.is_from_previous_run());
+    }
+
+    #[test]
+    fn journal_without_launch_id_is_a_previous_run() {
+        let json = r#"{"pid":42,"saved_at":"","playlist_name":"Sunday","unsaved":false,"items":[]}"#;
+        let journal: SessionJournal = serde_json::from_str(json).unwrap();
+        assert!(journal.is_from_previous_run());
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use super::rules::NameRule;
 
 fn default_duration_val() -> String {
     "00:05:00".to_string()
@@ -22,6 +23,12 @@ pub struct AppSettings {
     pub favorite_durations: Vec<String>,
     #[serde(default)]
     pub favorite_end_times: Vec<String>,
+    /// Most recently opened or saved project files, newest first
+    #[serde(default)]
+    pub recent_projects: Vec<String>,
+    /// Name-pattern rules applied to items added from a playlist, first match wins
+    #[serde(default)]
+    pub rules: Vec<NameRule>,
 }
 
 impl Default for AppSettings {
@@ -35,6 +42,8 @@ impl Default for AppSettings {
             default_end_time: default_end_time_val(),
             favorite_durations: vec![],
             favorite_end_times: vec![],
+            recent_projects: vec![],
+            rules: vec![],
         }
     }
 }
@@ -42,9 +51,7 @@ impl Default for AppSettings {
 impl AppSettings {
     /// Get the path to the settings file in the user's config directory
     fn settings_path() -> std::path::PathBuf {
-        let config_dir = dirs::config_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
-        let app_dir = config_dir.join("pp2ot-bridge");
-        app_dir.join("settings.json")
+        Self::config_dir().join("settings.json")
     }
 
     pub fn load() -> Self {
@@ -57,6 +64,23 @@ impl AppSettings {
         Self::default()
     }
 
+    /// Move `path` to the front of the recent projects list and persist it
+    pub fn remember_project(path: &std::path::Path) -> std::io::Result<()> {
+        const MAX_RECENT_PROJECTS: usize = 8;
+        let path = path.to_string_lossy().to_string();
+        let mut settings = Self::load();
+        settings.recent_projects.retain(|p| p != &path);
+        settings.recent_projects.insert(0, path);
+        settings.recent_projects.truncate(MAX_RECENT_PROJECTS);
+        settings.save()
+    }
+
+    /// Get the application config directory, shared by settings and other app data
+    pub fn config_dir() -> std::path::PathBuf {
+        let config_dir = dirs::config_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+        config_dir.join("pp2ot-bridge")
+    }
+
     pub fn save(&self) -> std::io::Result<()> {
         let path = Self::settings_path();
         // Ensure the config directory exists
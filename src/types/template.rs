@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use super::formatter::{FormatterItem, TimedEntry};
+use super::propresenter::PlaylistItem;
+use super::rules::{apply_rules, NameRule, RuleSet};
+use super::settings::AppSettings;
+use super::time::{Duration, TimeOfDay};
+
+/// Placeholder entry in a template group, filled from a playlist item when the template is applied
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateSlot {
+    pub label: String,
+    /// Fill with the first playlist item whose name contains this text (case-insensitive).
+    /// Empty takes the next playlist item after the previously filled slot.
+    #[serde(default)]
+    pub match_text: String,
+    pub duration: Duration,
+    pub count_to_end: bool,
+    pub link_start: bool,
+    /// Entry colour; empty keeps the colour from the rules
+    #[serde(default)]
+    pub color: String,
+    #[serde(default)]
+    pub cue: String,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl TemplateSlot {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            match_text: String::new(),
+            duration: Duration::from_ms(300_000),
+            count_to_end: false,
+            link_start: true,
+            color: String::new(),
+            cue: String::new(),
+            note: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateGroup {
+    pub name: String,
+    pub color: String,
+    pub slots: Vec<TemplateSlot>,
+}
+
+impl TemplateGroup {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), color: "#779BE7".to_string(), slots: vec![] }
+    }
+}
+
+/// Reusable service skeleton: named groups of placeholder entries with default timings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceTemplate {
+    pub name: String,
+    pub groups: Vec<TemplateGroup>,
+}
+
+/// Result of applying a template to a playlist
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateFill {
+    pub items: Vec<FormatterItem>,
+    /// Labels of slots no playlist item could fill
+    pub unfilled: Vec<String>,
+    /// Names of playlist items no slot picked up
+    pub unused: Vec<String>,
+}
+
+impl ServiceTemplate {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), groups: vec![] }
+    }
+
+    fn templates_path() -> std::path::PathBuf {
+        AppSettings::config_dir().join("templates.json")
+    }
+
+    pub fn load_all() -> Vec<Self> {
+        std::fs::read_to_string(Self::templates_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_all(templates: &[Self]) -> std::io::Result<()> {
+        let path = Self::templates_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(templates)?)
+    }
+
+    /// Build formatter groups from the template, filling each slot with a playlist item.
+    /// Rules set the entry colour; the slot's own timings and fields take precedence over theirs.
+    pub fn apply(&self, playlist: &[PlaylistItem], rules: &[NameRule]) -> TemplateFill {
+        let rules = RuleSet::new(rules);
+        let mut used = vec![false; playlist.len()];
+        // Position after the last filled slot, where unmatched slots continue from
+        let mut cursor = 0;
+        let mut unfilled = Vec::new();
+
+        let items = self
+            .groups
+            .iter()
+            .enumerate()
+            .map(|(group_idx, group)| {
+                let mut entries = Vec::new();
+                for slot in &group.slots {
+                    let needle = slot.match_text.trim().to_lowercase();
+                    let found = if needle.is_empty() {
+                        (cursor..playlist.len()).find(|&i| !used[i])
+                    } else {
+                        (0..playlist.len())
+                            .find(|&i| !used[i] && playlist[i].id.name.to_lowercase().contains(&needle))
+                    };
+                    let Some(i) = found else {
+                        unfilled.push(format!("{} / {}", group.name, slot.label));
+                        continue;
+                    };
+                    used[i] = true;
+                    cursor = i + 1;
+                    let mut entry = TimedEntry::from_playlist_item(&playlist[i], slot.duration, TimeOfDay::MIDNIGHT);
+                    // The template decides placement, so a rule's group is ignored here
+                    apply_rules(&rules, &playlist[i], &mut entry);
+                    entry.duration = slot.duration;
+                    entry.count_to_end = slot.count_to_end;
+                    entry.link_start = slot.link_start;
+                    if !slot.color.is_empty() {
+                        entry.color = slot.color.clone();
+                    }
+                    if !slot.cue.is_empty() {
+                        entry.cue = slot.cue.clone();
+                    }
+                    if !slot.note.is_empty() {
+                        entry.note = slot.note.clone();
+                    }
+                    entries.push(entry);
+                }
+                FormatterItem::Group {
+                    id: format!("group-{}", group_idx + 1),
+                    name: group.name.clone(),
+                    color: group.color.clone(),
+                    entries,
+                    collapsed: false,
+                    target_duration: None,
+                }
+            })
+            .collect();
+
+        let unused = playlist
+            .iter()
+            .zip(&used)
+            .filter(|(_, used)| !**used)
+            .map(|(item, _)| item.id.name.clone())
+            .collect();
+
+        TemplateFill { items, unfilled, unused }
+    }
+}
+
+/// Swap the playlist entries in a plan for a filled template. References, delays,
+/// milestones and manual entries stay where they are; groups left with nothing in
+/// them are dropped. Returns how many entries were replaced.
+pub fn replace_with_template(items: &mut Vec<FormatterItem>, filled: Vec<FormatterItem>) -> usize {
+    let mut replaced = 0;
+    items.retain_mut(|item| match item {
+        FormatterItem::Standalone(entry) if !entry.manual => {
+            replaced += 1;
+            false
+        }
+        FormatterItem::Group { entries, .. } => {
+            let before = entries.len();
+            entries.retain(|entry| entry.manual);
+            replaced += before - entries.len();
+            before == 0 || !entries.is_empty()
+        }
+        _ => true,
+    });
+    items.extend(filled);
+    replaced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Dictionary;
+
+    fn playlist_item(name: &str, index: usize) -> PlaylistItem {
+        PlaylistItem {
+            id: Dictionary { uuid: format!("uuid-{}", index), name: name.to_string(), index },
+            item_type: "presentation".to_string(),
+            duration: None,
+            presentation_info: None,
+        }
+    }
+
+    fn template() -> ServiceTemplate {
+        let mut group = TemplateGroup::new("Worship");
+        group.slots = vec![TemplateSlot::new("Song 1"), TemplateSlot::new("Song 2")];
+        ServiceTemplate { name: "Sunday".to_string(), groups: vec![group] }
+    }
+
+    #[test]
+    fn apply_fills_slots_in_order() {
+        let playlist = vec![playlist_item("Amazing Grace", 0), playlist_item("Cornerstone", 1), playlist_item("Notices", 2)];
+        let fill = template().apply(&playlist, &[]);
+        let FormatterItem::Group { entries, .. } = &fill.items[0] else { panic!("expected a group") };
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Amazing Grace", "Cornerstone"]);
+        assert!(fill.unfilled.is_empty());
+        assert_eq!(fill.unused, ["Notices"]);
+    }
+
+    #[test]
+    fn applying_keeps_non_playlist_items() {
+        let playlist = vec![playlist_item("Amazing Grace", 0), playlist_item("Cornerstone", 1)];
+        let from_playlist = |i: usize| TimedEntry::from_playlist_item(&playlist[i], Duration::from_ms(60_000), TimeOfDay::MIDNIGHT);
+        let manual = TimedEntry::manual("Welcome".to_string(), Duration::from_ms(60_000), TimeOfDay::MIDNIGHT);
+        let grouped_manual = TimedEntry::manual("Offering".to_string(), Duration::from_ms(60_000), TimeOfDay::MIDNIGHT);
+        let mut items = vec![
+            FormatterItem::new_milestone("Doors open"),
+            FormatterItem::Standalone(manual.clone()),
+            FormatterItem::Standalone(from_playlist(0)),
+            FormatterItem::new_delay(Duration::from_ms(120_000)),
+            FormatterItem::Group {
+                id: "group-1".to_string(),
+                name: "Old".to_string(),
+                color: String::new(),
+                entries: vec![from_playlist(1)],
+                collapsed: false,
+                target_duration: None,
+            },
+            FormatterItem::Group {
+                id: "group-2".to_string(),
+                name: "Mixed".to_string(),
+                color: String::new(),
+                entries: vec![from_playlist(0), grouped_manual.clone()],
+                collapsed: false,
+                target_duration: None,
+            },
+        ];
+        let fill = template().apply(&playlist, &[]);
+        assert_eq!(replace_with_template(&mut items, fill.items), 3);
+
+        assert!(matches!(&items[0], FormatterItem::Milestone { title, .. } if title == "Doors open"));
+        assert_eq!(items[1], FormatterItem::Standalone(manual));
+        assert!(matches!(items[2], FormatterItem::Delay { .. }));
+        assert!(matches!(&items[3], FormatterItem::Group { name, entries, .. } if name == "Mixed" && *entries == [grouped_manual.clone()]));
+        assert!(matches!(&items[4], FormatterItem::Group { name, entries, .. } if name == "Worship" && entries.len() == 2));
+        assert_eq!(items.len(), 5);
+    }
+}
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use crate::utils::format_ms_to_duration;
+
+pub const MS_PER_SECOND: u64 = 1000;
+pub const MS_PER_MINUTE: u64 = 60 * MS_PER_SECOND;
+pub const MS_PER_HOUR: u64 = 60 * MS_PER_MINUTE;
+pub const MS_PER_DAY: u64 = 24 * MS_PER_HOUR;
+
+/// A length of time in milliseconds (e.g. how long an event runs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Duration(u64);
+
+impl Duration {
+    pub const fn from_ms(ms: u64) -> Self {
+        Self(ms)
+    }
+
+    pub fn as_ms(self) -> u64 {
+        self.0
+    }
+}
+
+impl std::ops::Add for Duration {
+    type Output = Duration;
+
+    fn add(self, other: Duration) -> Duration {
+        Duration(self.0.saturating_add(other.0))
+    }
+}
+
+impl std::fmt::Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_ms_to_duration(self.0))
+    }
+}
+
+/// A point on the schedule: milliseconds from midnight plus a day offset.
+///
+/// Mirrors Ontime, which stores `timeStart`/`timeEnd` as ms from midnight and
+/// tracks events running into following days with `dayOffset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+pub struct TimeOfDay {
+    // Field order matters for the derived Ord: day first, then time within the day
+    #[serde(default)]
+    day_offset: u32,
+    ms: u64,
+}
+
+impl TimeOfDay {
+    pub const MIDNIGHT: TimeOfDay = TimeOfDay { day_offset: 0, ms: 0 };
+
+    /// `ms` past `MS_PER_DAY` rolls over into the day offset
+    pub fn new(ms: u64, day_offset: u32) -> Self {
+        Self::from_schedule_ms(day_offset as u64 * MS_PER_DAY + ms)
+    }
+
+    /// Build from milliseconds since midnight of the first day. Times past the
+    /// last representable day stop there.
+    pub fn from_schedule_ms(total: u64) -> Self {
+        match u32::try_from(total / MS_PER_DAY) {
+            Ok(day_offset) => Self { day_offset, ms: total % MS_PER_DAY },
+            Err(_) => Self { day_offset: u32::MAX, ms: MS_PER_DAY - 1 },
+        }
+    }
+
+    /// Milliseconds since midnight of the first day
+    pub fn schedule_ms(self) -> u64 {
+        self.day_offset as u64 * MS_PER_DAY + self.ms
+    }
+
+    /// Milliseconds since midnight of its own day, as Ontime's `timeEnd` expects
+    pub fn ms_of_day(self) -> u64 {
+        self.ms
+    }
+
+    /// Days after the first day of the schedule
+    pub fn day_offset(self) -> u32 {
+        self.day_offset
+    }
+
+    pub fn is_midnight(self) -> bool {
+        self == Self::MIDNIGHT
+    }
+
+    /// End time of an Ontime event. An end earlier than the start means the
+    /// event crosses midnight, so the end lands on the following day.
+    pub fn ontime_end(time_start: u64, time_end: u64, day_offset: u32) -> Self {
+        let crosses_midnight = time_end < time_start;
+        Self::new(time_end, day_offset.saturating_add(crosses_midnight as u32))
+    }
+
+    /// Time reached after running for `duration` from here
+    pub fn after(self, duration: Duration) -> Self {
+        Self::from_schedule_ms(self.schedule_ms().saturating_add(duration.as_ms()))
+    }
+
+    /// Time `duration` earlier, stopping at midnight of the first day
+    pub fn before(self, duration: Duration) -> Self {
+        Self::from_schedule_ms(self.schedule_ms().saturating_sub(duration.as_ms()))
+    }
+
+    /// Move by a signed number of milliseconds, stopping at midnight of the first day
+    pub fn shifted(self, delta_ms: i64) -> Self {
+        Self::from_schedule_ms(self.schedule_ms().saturating_add_signed(delta_ms))
+    }
+
+    /// Place a time entered without a day offset so it doesn't fall before
+    /// `previous`, e.g. 00:30 after a 23:00 start becomes 00:30 on the next day.
+    /// Times that already carry an explicit day offset are left alone.
+    pub fn roll_forward_from(self, previous: TimeOfDay) -> Self {
+        if self.day_offset > 0 || self >= previous {
+            return self;
+        }
+        let candidate = Self::new(self.ms, previous.day_offset);
+        if candidate >= previous {
+            candidate
+        } else {
+            Self::new(self.ms, previous.day_offset.saturating_add(1))
+        }
+    }
+}
+
+impl std::fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_ms_to_duration(self.ms))?;
+        if self.day_offset > 0 {
+            write!(f, " +{}d", self.day_offset)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use super::settings::AppSettings;
+use super::time::{Duration, MS_PER_SECOND};
+
+/// Only this many of the most recent observations are kept per presentation
+const MAX_SAMPLES: usize = 30;
+
+/// Anything shorter was skipped past rather than actually run
+const MIN_SAMPLE: Duration = Duration::from_ms(5 * MS_PER_SECOND);
+
+/// Observed run times of one ProPresenter presentation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimingRecord {
+    /// Empty when it has only been seen by name (e.g. through Ontime)
+    #[serde(default)]
+    pub presentation_uuid: String,
+    pub name: String,
+    /// Oldest first
+    pub durations: Vec<Duration>,
+}
+
+impl TimingRecord {
+    /// Middle observation, rounded to whole seconds
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.durations.clone();
+        sorted.sort();
+        let mid = sorted.len() / 2;
+        let ms = match sorted.len() {
+            0 => 0,
+            n if n % 2 == 0 => (sorted[mid - 1].as_ms() + sorted[mid].as_ms()) / 2,
+            _ => sorted[mid].as_ms(),
+        };
+        Duration::from_ms((ms + MS_PER_SECOND / 2) / MS_PER_SECOND * MS_PER_SECOND)
+    }
+
+    /// Shortest and longest observation
+    pub fn spread(&self) -> (Duration, Duration) {
+        let min = self.durations.iter().min().copied().unwrap_or_default();
+        let max = self.durations.iter().max().copied().unwrap_or_default();
+        (min, max)
+    }
+
+    /// Tooltip text, e.g. "Median 00:04:30 over 6 runs (00:04:02 – 00:05:11)"
+    pub fn summary(&self) -> String {
+        let (min, max) = self.spread();
+        let runs = self.durations.len();
+        format!(
+            "Median {} over {} run{} ({} – {})",
+            self.median(),
+            runs,
+            if runs == 1 { "" } else { "s" },
+            min,
+            max
+        )
+    }
+}
+
+/// Local store of how long presentations actually ran, keyed by presentation
+/// UUID with the name as a fallback
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimingHistory {
+    pub records: Vec<TimingRecord>,
+}
+
+impl TimingHistory {
+    fn history_path() -> std::path::PathBuf {
+        AppSettings::config_dir().join("timing_history.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::history_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = Self::history_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// By UUID when known. Two presentations can share a name, so with a UUID the
+    /// name only matches a record that hasn't got one yet.
+    fn position(&self, presentation_uuid: Option<&str>, name: &str) -> Option<usize> {
+        let same_name = |r: &TimingRecord| r.name.eq_ignore_ascii_case(name.trim());
+        match presentation_uuid.filter(|uuid| !uuid.is_empty()) {
+            Some(uuid) => self
+                .records
+                .iter()
+                .position(|r| r.presentation_uuid == uuid)
+                .or_else(|| self.records.iter().position(|r| r.presentation_uuid.is_empty() && same_name(r))),
+            None => self.records.iter().position(same_name),
+        }
+    }
+
+    /// Record for a presentation, by UUID when known and otherwise by name
+    pub fn lookup(&self, presentation_uuid: Option<&str>, name: &str) -> Option<&TimingRecord> {
+        self.position(presentation_uuid, name).map(|idx| &self.records[idx])
+    }
+
+    /// Add one observed run. Returns false when it was too short to count.
+    pub fn record(&mut self, presentation_uuid: Option<&str>, name: &str, duration: Duration) -> bool {
+        if duration < MIN_SAMPLE || name.trim().is_empty() {
+            return false;
+        }
+        let uuid = presentation_uuid.filter(|uuid| !uuid.is_empty());
+        let idx = match self.position(uuid, name) {
+            Some(idx) => idx,
+            None => {
+                self.records.push(TimingRecord {
+                    presentation_uuid: String::new(),
+                    name: name.trim().to_string(),
+                    durations: vec![],
+                });
+                self.records.len() - 1
+            }
+        };
+        let record = &mut self.records[idx];
+        // A record first seen by name picks up the UUID once ProPresenter reports it
+        if let Some(uuid) = uuid {
+            record.presentation_uuid = uuid.to_string();
+        }
+        record.name = name.trim().to_string();
+        record.durations.push(duration);
+        if record.durations.len() > MAX_SAMPLES {
+            record.durations.remove(0);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_ms(s * MS_PER_SECOND)
+    }
+
+    fn record(durations: &[u64]) -> TimingRecord {
+        TimingRecord {
+            presentation_uuid: String::new(),
+            name: "Song".to_string(),
+            durations: durations.iter().map(|ms| Duration::from_ms(*ms)).collect(),
+        }
+    }
+
+    #[test]
+    fn median_rounds_to_seconds() {
+        assert_eq!(record(&[]).median(), Duration::default());
+        assert_eq!(record(&[240_000, 200_400, 300_000]).median(), secs(240));
+        // Even counts average the middle two: 200.4s and 240s give 220.2s
+        assert_eq!(record(&[300_000, 240_000, 200_400, 100_000]).median(), secs(220));
+        assert_eq!(record(&[200_600]).median(), secs(201));
+        assert_eq!(record(&[240_000, 200_400, 300_000]).spread(), (Duration::from_ms(200_400), secs(300)));
+    }
+
+    #[test]
+    fn short_runs_and_blank_names_are_ignored() {
+        let mut history = TimingHistory::default();
+        assert!(!history.record(None, "Song", secs(4)));
+        assert!(!history.record(None, "  ", secs(60)));
+        assert!(history.records.is_empty());
+    }
+
+    #[test]
+    fn name_records_adopt_a_uuid_once() {
+        let mut history = TimingHistory::default();
+        assert!(history.record(None, "Amazing Grace", secs(240)));
+        assert!(history.record(Some("uuid-a"), "amazing grace", secs(250)));
+        assert_eq!(history.records.len(), 1);
+        assert_eq!(history.records[0].presentation_uuid, "uuid-a");
+
+        // Another presentation with the same name gets its own record
+        assert!(history.record(Some("uuid-b"), "Amazing Grace", secs(300)));
+        assert_eq!(history.records.len(), 2);
+        assert_eq!(history.records[0].presentation_uuid, "uuid-a");
+        assert_eq!(history.records[0].durations.len(), 2);
+        assert_eq!(history.lookup(Some("uuid-b"), "Amazing Grace").map(|r| r.median()), Some(secs(300)));
+        assert_eq!(history.lookup(Some("uuid-c"), "Amazing Grace"), None);
+        // Ontime only knows the name
+        assert!(history.lookup(None, "AMAZING GRACE").is_some());
+    }
+
+    #[test]
+    fn keeps_the_latest_samples() {
+        let mut history = TimingHistory::default();
+        for run in 0..MAX_SAMPLES as u64 + 5 {
+            history.record(Some("uuid"), "Song", secs(60 + run));
+        }
+        let durations = &history.records[0].durations;
+        assert_eq!(durations.len(), MAX_SAMPLES);
+        assert_eq!(durations[0], secs(65));
+        assert_eq!(durations.last(), Some(&secs(60 + MAX_SAMPLES as u64 + 4)));
+    }
+}
@@ -0,0 +1,23 @@
+// Utils module - time parsing and formatting helpers
+
+mod time_expr;
+
+pub use time_expr::*;
+
+/// Format milliseconds to duration string (HH:MM:SS)
+pub fn format_ms_to_duration(ms: u64) -> String {
+    let seconds = ms / 1000;
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+    format!("{:02}:{:02}:{:02}", hours, minutes, secs)
+}
+
+/// Escape text for use in generated HTML documents
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -0,0 +1,427 @@
+use crate::types::{Duration, TimeField, TimeOfDay, MS_PER_DAY, MS_PER_HOUR, MS_PER_MINUTE, MS_PER_SECOND};
+
+/// Why a time expression was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeParseError {
+    Empty,
+    /// The input doesn't match any supported form
+    Invalid(String),
+    /// A component is outside its allowed range (e.g. 75 minutes in `HH:MM:SS`)
+    OutOfRange(String),
+    /// `ref` was used but there is no reference item to resolve it against
+    NoReference,
+    /// A relative expression landed before 00:00:00
+    Negative,
+}
+
+impl std::fmt::Display for TimeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeParseError::Empty => write!(f, "Enter a time"),
+            TimeParseError::Invalid(input) => write!(
+                f,
+                "Can't read \"{}\" - try 05:00, 90s, 1h30m, 4:30pm, +5m or ref+10m",
+                input
+            ),
+            TimeParseError::OutOfRange(msg) => write!(f, "{}", msg),
+            TimeParseError::NoReference => write!(f, "\"ref\" needs a reference item in the formatter"),
+            TimeParseError::Negative => write!(f, "Result is before 00:00:00"),
+        }
+    }
+}
+
+impl std::error::Error for TimeParseError {}
+
+/// Furthest day an end time may land on, counting the first day as 0
+pub const MAX_DAY_OFFSET: u64 = 30;
+
+fn too_large() -> TimeParseError {
+    TimeParseError::OutOfRange("Value is too large".to_string())
+}
+
+/// Reject end times past the last supported day. Durations have no upper bound.
+fn check_range(ms: u64, field: TimeField) -> Result<u64, TimeParseError> {
+    if field == TimeField::EndTime && ms >= (MAX_DAY_OFFSET + 1) * MS_PER_DAY {
+        return Err(TimeParseError::OutOfRange(format!(
+            "End time must be within {} days of the start",
+            MAX_DAY_OFFSET
+        )));
+    }
+    Ok(ms)
+}
+
+/// Values that relative expressions are resolved against
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimeExprContext {
+    /// The value being edited, used by `+5m` / `-30s`
+    pub current: u64,
+    /// End time of the active reference item, used by `ref+10m`
+    pub reference: Option<u64>,
+}
+
+/// Parse a time expression into milliseconds (since midnight of the first day for end times).
+///
+/// Accepted forms:
+/// - `HH:MM:SS`
+/// - `MM:SS` for durations, `HH:MM` for end times
+/// - unit spans: `90s`, `5m`, `1h30m`, `1h 30m 15s`
+/// - 12-hour clock (end times only): `4:30pm`, `11am`
+/// - relative to the current value: `+5m`, `-30s`
+/// - relative to the reference item's end: `ref`, `ref+10m`, `ref-2m`
+/// - a day offset after an end time: `00:30 +1d`
+pub fn parse_time_expr(input: &str, field: TimeField, ctx: TimeExprContext) -> Result<u64, TimeParseError> {
+    let text = input.trim().to_ascii_lowercase();
+    if text.is_empty() {
+        return Err(TimeParseError::Empty);
+    }
+    let invalid = || TimeParseError::Invalid(input.trim().to_string());
+
+    if field == TimeField::EndTime {
+        if let Some((time, days)) = split_day_suffix(&text) {
+            let base = parse_time_expr(time, field, ctx)?;
+            let total = days.checked_mul(MS_PER_DAY).and_then(|ms| ms.checked_add(base)).ok_or_else(too_large)?;
+            return check_range(total, field);
+        }
+    }
+
+    if let Some(rest) = text.strip_prefix("ref") {
+        let base = ctx.reference.ok_or(TimeParseError::NoReference)?;
+        let rest = rest.trim();
+        if rest.is_empty() {
+            return check_range(base, field);
+        }
+        let result = apply_offset(base, rest).ok_or_else(invalid)??;
+        return check_range(result, field);
+    }
+
+    if text.starts_with('+') || text.starts_with('-') {
+        let result = apply_offset(ctx.current, &text).ok_or_else(invalid)??;
+        return check_range(result, field);
+    }
+
+    if let Some(clock) = text.strip_suffix("am").or_else(|| text.strip_suffix("pm")) {
+        if field == TimeField::Duration {
+            return Err(TimeParseError::OutOfRange("am/pm only applies to end times".to_string()));
+        }
+        let is_pm = text.ends_with("pm");
+        return parse_twelve_hour(clock.trim(), is_pm).ok_or_else(invalid)?;
+    }
+
+    if text.contains(':') {
+        return parse_clock(&text, field).ok_or_else(invalid)?;
+    }
+
+    let total = parse_span(&text).ok_or_else(invalid)??;
+    // A bare span is a time of day for end times, the same as a clock; later days take a `+1d` suffix
+    if field == TimeField::EndTime && total >= MS_PER_DAY {
+        return Err(TimeParseError::OutOfRange("End time must be before 24:00:00".to_string()));
+    }
+    check_range(total, field)
+}
+
+/// Parse a duration expression (`05:00`, `90s`, `1h30m`, ...) with no relative context
+pub fn parse_duration(input: &str) -> Result<Duration, TimeParseError> {
+    parse_time_expr(input, TimeField::Duration, TimeExprContext::default()).map(Duration::from_ms)
+}
+
+/// Parse an end time expression (`10:30`, `4:30pm`, `00:30 +1d`, ...) with no relative context
+pub fn parse_end_time(input: &str) -> Result<TimeOfDay, TimeParseError> {
+    parse_time_expr(input, TimeField::EndTime, TimeExprContext::default()).map(TimeOfDay::from_schedule_ms)
+}
+
+/// Split `"00:30 +1d"` into `("00:30", 1)`. A leading `+` is a relative offset, not a suffix.
+/// A day count too large for `u64` comes back as `u64::MAX` so it fails the range check.
+fn split_day_suffix(text: &str) -> Option<(&str, u64)> {
+    let idx = text.rfind('+')?;
+    let time = text[..idx].trim();
+    let days = text[idx + 1..].trim().strip_suffix('d')?;
+    if time.is_empty() || time == "ref" || days.is_empty() || !days.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((time, days.parse().unwrap_or(u64::MAX)))
+}
+
+/// Apply a signed span such as `+5m` or `- 1h30m` to `base`.
+/// Returns `None` if the offset itself can't be read.
+fn apply_offset(base: u64, offset: &str) -> Option<Result<u64, TimeParseError>> {
+    let offset = offset.trim();
+    let (negative, span) = match offset.chars().next()? {
+        '+' => (false, &offset[1..]),
+        '-' => (true, &offset[1..]),
+        _ => return None,
+    };
+    let span = span.trim();
+    let amount = if span.contains(':') {
+        parse_clock(span, TimeField::Duration)?
+    } else {
+        parse_span(span)?
+    };
+    Some(amount.and_then(|amount| {
+        if negative {
+            base.checked_sub(amount).ok_or(TimeParseError::Negative)
+        } else {
+            base.checked_add(amount).ok_or_else(too_large)
+        }
+    }))
+}
+
+/// `HH:MM:SS`, or two-part `MM:SS` (duration) / `HH:MM` (end time)
+fn parse_clock(text: &str, field: TimeField) -> Option<Result<u64, TimeParseError>> {
+    let parts: Vec<&str> = text.split(':').map(str::trim).collect();
+    if parts.iter().any(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    let nums: Vec<u64> = match parts.iter().map(|p| p.parse()).collect::<Result<_, _>>() {
+        Ok(nums) => nums,
+        Err(_) => return Some(Err(too_large())),
+    };
+
+    let (h, m, s) = match (nums.as_slice(), field) {
+        ([h, m, s], _) => (*h, *m, *s),
+        ([m, s], TimeField::Duration) => (0, *m, *s),
+        ([h, m], TimeField::EndTime) => (*h, *m, 0),
+        _ => return None,
+    };
+
+    // The leading component of a duration may overflow (e.g. 90:00 = 90 minutes)
+    let leading_is_free = field == TimeField::Duration;
+    if nums.len() == 3 && (m > 59 || s > 59) {
+        return Some(Err(TimeParseError::OutOfRange("Minutes and seconds must be 0-59".to_string())));
+    }
+    if nums.len() == 2 && field == TimeField::Duration && s > 59 {
+        return Some(Err(TimeParseError::OutOfRange("Seconds must be 0-59".to_string())));
+    }
+    if nums.len() == 2 && field == TimeField::EndTime && m > 59 {
+        return Some(Err(TimeParseError::OutOfRange("Minutes must be 0-59".to_string())));
+    }
+
+    let Some(total) = h
+        .checked_mul(MS_PER_HOUR)
+        .and_then(|ms| ms.checked_add(m.checked_mul(MS_PER_MINUTE)?))
+        .and_then(|ms| ms.checked_add(s * MS_PER_SECOND))
+    else {
+        return Some(Err(too_large()));
+    };
+    if !leading_is_free && total >= MS_PER_DAY {
+        return Some(Err(TimeParseError::OutOfRange("End time must be before 24:00:00".to_string())));
+    }
+    Some(Ok(total))
+}
+
+/// `4:30`, `11`, `12:05:30` followed by am/pm
+fn parse_twelve_hour(clock: &str, is_pm: bool) -> Option<Result<u64, TimeParseError>> {
+    let parts: Vec<&str> = clock.split(':').map(str::trim).collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    let nums: Vec<u64> = parts
+        .iter()
+        .map(|p| if p.is_empty() || !p.chars().all(|c| c.is_ascii_digit()) { None } else { p.parse().ok() })
+        .collect::<Option<_>>()?;
+    let hour = nums[0];
+    let minute = nums.get(1).copied().unwrap_or(0);
+    let second = nums.get(2).copied().unwrap_or(0);
+
+    if !(1..=12).contains(&hour) {
+        return Some(Err(TimeParseError::OutOfRange("12-hour times need an hour of 1-12".to_string())));
+    }
+    if minute > 59 || second > 59 {
+        return Some(Err(TimeParseError::OutOfRange("Minutes and seconds must be 0-59".to_string())));
+    }
+
+    let hour24 = match (hour, is_pm) {
+        (12, false) => 0,
+        (12, true) => 12,
+        (h, false) => h,
+        (h, true) => h + 12,
+    };
+    Some(Ok(hour24 * MS_PER_HOUR + minute * MS_PER_MINUTE + second * MS_PER_SECOND))
+}
+
+/// Unit spans like `90s`, `5m`, `1h30m`, `1h 30m 15s`, `1d`. Every number needs a unit.
+fn parse_span(text: &str) -> Option<Result<u64, TimeParseError>> {
+    let mut total = 0u64;
+    let mut digits = String::new();
+    let mut seen_unit = false;
+
+    for c in text.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            ' ' if digits.is_empty() => {}
+            'd' | 'h' | 'm' | 's' => {
+                if digits.is_empty() {
+                    return None;
+                }
+                let unit = match c {
+                    'd' => MS_PER_DAY,
+                    'h' => MS_PER_HOUR,
+                    'm' => MS_PER_MINUTE,
+                    _ => MS_PER_SECOND,
+                };
+                let Some(sum) = digits
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(|value| value.checked_mul(unit))
+                    .and_then(|ms| ms.checked_add(total))
+                else {
+                    return Some(Err(too_large()));
+                };
+                total = sum;
+                digits.clear();
+                seen_unit = true;
+            }
+            _ => return None,
+        }
+    }
+
+    if !digits.is_empty() || !seen_unit {
+        return None;
+    }
+    Some(Ok(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duration(input: &str) -> Result<u64, TimeParseError> {
+        parse_time_expr(input, TimeField::Duration, TimeExprContext::default())
+    }
+
+    fn end_time(input: &str) -> Result<u64, TimeParseError> {
+        parse_time_expr(input, TimeField::EndTime, TimeExprContext::default())
+    }
+
+    fn with_context(input: &str, field: TimeField, current: u64, reference: Option<u64>) -> Result<u64, TimeParseError> {
+        parse_time_expr(input, field, TimeExprContext { current, reference })
+    }
+
+    const fn hms(h: u64, m: u64, s: u64) -> u64 {
+        h * MS_PER_HOUR + m * MS_PER_MINUTE + s * MS_PER_SECOND
+    }
+
+    #[test]
+    fn clock_forms() {
+        assert_eq!(duration("01:02:03"), Ok(hms(1, 2, 3)));
+        assert_eq!(duration("05:00"), Ok(hms(0, 5, 0)));
+        assert_eq!(duration("90:00"), Ok(hms(1, 30, 0)));
+        assert_eq!(end_time("10:30"), Ok(hms(10, 30, 0)));
+        assert_eq!(end_time("23:59:59"), Ok(hms(23, 59, 59)));
+    }
+
+    #[test]
+    fn unit_spans() {
+        assert_eq!(duration("90s"), Ok(hms(0, 1, 30)));
+        assert_eq!(duration("5m"), Ok(hms(0, 5, 0)));
+        assert_eq!(duration("1h30m"), Ok(hms(1, 30, 0)));
+        assert_eq!(duration("1h 30m 15s"), Ok(hms(1, 30, 15)));
+        assert_eq!(duration("1d"), Ok(MS_PER_DAY));
+    }
+
+    #[test]
+    fn twelve_hour_clock() {
+        assert_eq!(end_time("4:30pm"), Ok(hms(16, 30, 0)));
+        assert_eq!(end_time("11am"), Ok(hms(11, 0, 0)));
+        assert_eq!(end_time("12am"), Ok(0));
+        assert_eq!(end_time("12:05:30pm"), Ok(hms(12, 5, 30)));
+        assert!(matches!(duration("4pm"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(end_time("13pm"), Err(TimeParseError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn relative_to_current() {
+        let current = hms(10, 0, 0);
+        assert_eq!(with_context("+5m", TimeField::EndTime, current, None), Ok(hms(10, 5, 0)));
+        assert_eq!(with_context("-30s", TimeField::Duration, current, None), Ok(hms(9, 59, 30)));
+        assert_eq!(with_context("+ 1:00", TimeField::Duration, current, None), Ok(hms(10, 1, 0)));
+        assert_eq!(with_context("-11h", TimeField::EndTime, current, None), Err(TimeParseError::Negative));
+    }
+
+    #[test]
+    fn relative_to_reference() {
+        let reference = Some(hms(18, 0, 0));
+        assert_eq!(with_context("ref", TimeField::EndTime, 0, reference), Ok(hms(18, 0, 0)));
+        assert_eq!(with_context("ref+10m", TimeField::EndTime, 0, reference), Ok(hms(18, 10, 0)));
+        assert_eq!(with_context("ref-2m", TimeField::EndTime, 0, reference), Ok(hms(17, 58, 0)));
+        assert_eq!(with_context("ref+1m", TimeField::EndTime, 0, None), Err(TimeParseError::NoReference));
+    }
+
+    #[test]
+    fn day_suffix() {
+        assert_eq!(end_time("00:30 +1d"), Ok(MS_PER_DAY + hms(0, 30, 0)));
+        assert_eq!(end_time("4:30pm +2d"), Ok(2 * MS_PER_DAY + hms(16, 30, 0)));
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!(duration("   "), Err(TimeParseError::Empty));
+        assert!(matches!(duration("abc"), Err(TimeParseError::Invalid(_))));
+        assert!(matches!(duration("90"), Err(TimeParseError::Invalid(_))));
+        assert!(matches!(duration("1:2:3:4"), Err(TimeParseError::Invalid(_))));
+        assert!(matches!(duration("01:75:00"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(duration("05:60"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(end_time("10:75"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(end_time("24:00"), Err(TimeParseError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn overflow_is_out_of_range() {
+        assert!(matches!(duration("9999999999999999h"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(duration("99999999999999999999999s"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(duration("9999999999999999:00"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(duration("9999999999999999:00:00"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(
+            with_context("+99999999999999d", TimeField::Duration, 0, None),
+            Err(TimeParseError::OutOfRange(_))
+        ));
+        assert!(matches!(
+            with_context("+5m", TimeField::Duration, u64::MAX, None),
+            Err(TimeParseError::OutOfRange(_))
+        ));
+        assert!(matches!(end_time("00:30 +99999999999999999999d"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(end_time("00:30 +999999999999999d"), Err(TimeParseError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn end_times_stay_in_range() {
+        let last_day = MAX_DAY_OFFSET * MS_PER_DAY;
+        assert_eq!(end_time(&format!("23:00 +{}d", MAX_DAY_OFFSET)), Ok(last_day + hms(23, 0, 0)));
+        assert!(matches!(end_time(&format!("00:00 +{}d", MAX_DAY_OFFSET + 1)), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(
+            with_context("+5m", TimeField::EndTime, last_day + hms(23, 58, 0), None),
+            Err(TimeParseError::OutOfRange(_))
+        ));
+        assert!(matches!(
+            with_context("ref+99999d", TimeField::EndTime, 0, Some(hms(18, 0, 0))),
+            Err(TimeParseError::OutOfRange(_))
+        ));
+        assert_eq!(with_context("+99999d", TimeField::Duration, 0, None), Ok(99_999 * MS_PER_DAY));
+    }
+
+    #[test]
+    fn end_time_spans_are_times_of_day() {
+        assert_eq!(end_time("10h30m"), Ok(hms(10, 30, 0)));
+        assert!(matches!(end_time("24:00"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(end_time("25h"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(end_time("1d"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(end_time("99999d"), Err(TimeParseError::OutOfRange(_))));
+        assert_eq!(end_time("1h +1d"), Ok(MS_PER_DAY + hms(1, 0, 0)));
+        assert_eq!(duration("25h"), Ok(hms(25, 0, 0)));
+    }
+
+    #[test]
+    fn bare_ref_is_range_checked() {
+        let far = Some((MAX_DAY_OFFSET + 1) * MS_PER_DAY);
+        assert!(matches!(with_context("ref", TimeField::EndTime, 0, far), Err(TimeParseError::OutOfRange(_))));
+        assert_eq!(with_context("ref", TimeField::Duration, 0, far), far.ok_or(TimeParseError::NoReference));
+    }
+
+    #[test]
+    fn huge_spans_do_not_overflow_times() {
+        let huge = parse_duration("99999999999d").unwrap();
+        assert_eq!(Duration::from_ms(u64::MAX - 1) + huge, Duration::from_ms(u64::MAX));
+        let end = TimeOfDay::new(0, 1).after(huge);
+        assert!(end > TimeOfDay::new(0, 1));
+        assert_eq!(end.day_offset(), u32::MAX);
+    }
+}